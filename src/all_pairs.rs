@@ -0,0 +1,167 @@
+use std::collections::BTreeMap;
+use crate::graph::{Graph,GraphView};
+use crate::weight::Weight;
+use crate::bellman_ford::BellmanFord;
+use crate::dijkstra::Dijkstra;
+use crate::paths::NegativeCycle;
+use crate::error::Result;
+use log::{info,debug};
+
+
+// shortest distance between every pair of vertexes, None where there is no path
+#[derive(Debug,Clone)]
+pub struct DistanceMatrix<W = i32> {
+    pub vertices: Vec<usize>,
+    pub distance: Vec<Vec<Option<W>>>,
+}
+
+impl<W: Weight> DistanceMatrix<W> {
+
+    pub fn new(vertices: Vec<usize>) -> Self {
+        let count = vertices.len();
+        DistanceMatrix {
+            vertices,
+            distance: vec![vec![None; count]; count],
+        }
+    }
+
+    fn index_of(&self, vertex: usize) -> Option<usize> {
+        self.vertices.binary_search(&vertex).ok()
+    }
+
+    pub fn get(&self, src: usize, dest: usize) -> Option<W> {
+        self.distance[self.index_of(src)?][self.index_of(dest)?]
+    }
+
+    // the shortest of all the shortest paths between two distinct vertexes,
+    // returned as (src, dest, distance)
+    pub fn min_shortest_path(&self) -> Option<(usize,usize,W)> {
+        let mut min : Option<(usize,usize,W)> = None;
+        for (i, row) in self.distance.iter().enumerate() {
+            for (j, d) in row.iter().enumerate() {
+                if let Some(d) = d {
+                    if i != j && min.is_none_or(|(_,_,cur)| *d < cur) {
+                        min = Some((self.vertices[i],self.vertices[j],*d));
+                    }
+                }
+            }
+        }
+        min
+    }
+
+}
+
+
+// O(V^3) dynamic programming over intermediate vertexes, best for small dense graphs
+pub fn floyd_warshall<W: Weight, G: GraphView<Weight=W>>(graph: &G) -> Result<DistanceMatrix<W>> {
+    info!("Starting Floyd-Warshall all pairs shortest paths");
+    let mut matrix = DistanceMatrix::new(graph.vertices().collect());
+    let count = matrix.vertices.len();
+    // next[i][j] is the vertex after i on the shortest path from i to j
+    let mut next = vec![vec![None; count]; count];
+
+    for (i, next_row) in next.iter_mut().enumerate() {
+        matrix.distance[i][i] = Some(W::ZERO);
+        next_row[i] = Some(i);
+        for (dest, weight) in graph.neighbors(matrix.vertices[i])? {
+            let j = matrix.index_of(dest).unwrap();
+            if matrix.distance[i][j].is_none_or(|cur| weight < cur) {
+                matrix.distance[i][j] = Some(weight);
+                next_row[j] = Some(j);
+            }
+        }
+    }
+
+    for k in 0..count {
+        for i in 0..count {
+            let Some(ik) = matrix.distance[i][k] else { continue };
+            for j in 0..count {
+                if let Some(kj) = matrix.distance[k][j] {
+                    if matrix.distance[i][j].is_none_or(|cur| ik + kj < cur) {
+                        matrix.distance[i][j] = Some(ik + kj);
+                        next[i][j] = next[i][k];
+                    }
+                }
+            }
+            // a vertex with a negative path back to itself is on a negative cycle
+            if matrix.distance[i][i].is_some_and(|d| d < W::ZERO) {
+                debug!("Vertex {} has a negative path to itself",matrix.vertices[i]);
+                return Err(negative_cycle(&next,&matrix.vertices,i).into());
+            }
+        }
+    }
+    Ok(matrix)
+}
+
+// follows the successors from i along its path back to itself, the first vertex seen
+// twice closes the cycle
+fn negative_cycle(next: &[Vec<Option<usize>>], vertices: &[usize], i: usize) -> NegativeCycle {
+    let mut order = Vec::<usize>::new();
+    let mut cur = i;
+    loop {
+        if let Some(pos) = order.iter().position(|v| *v == cur) {
+            let mut cycle : Vec<usize> = order[pos..].iter().map(|v| vertices[*v]).collect();
+            cycle.push(vertices[cur]);
+            return NegativeCycle { vertices: cycle };
+        }
+        order.push(cur);
+        cur = next[cur][i].unwrap_or(i);
+    }
+}
+
+
+// reweights the edges using Bellman-Ford potentials so they are all non-negative,
+// then runs Dijkstra from every vertex, best for large sparse graphs
+pub fn johnson<W: Weight, G: GraphView<Weight=W>>(graph: &G) -> Result<DistanceMatrix<W>> {
+    info!("Starting Johnson all pairs shortest paths");
+    let vertices : Vec<usize> = graph.vertices().collect();
+    let mut matrix = DistanceMatrix::new(vertices.clone());
+
+    // a directed copy of the graph (undirected edges are already listed from both ends)
+    // with a new vertex joined to every other vertex by a 0 weight edge, the distances
+    // from it are the potentials
+    let extra_vertex = vertices.last().map_or(0, |v| v + 1);
+    let mut extended = Graph::new();
+    for v in &vertices {
+        extended.add_edge(extra_vertex,*v,W::ZERO);
+        for (dest, weight) in graph.neighbors(*v)? {
+            extended.add_edge(*v,dest,weight);
+        }
+    }
+    let potential : BTreeMap<usize,W> = BellmanFord::new().shortest_paths(&extended,extra_vertex)?.distance;
+
+    let mut reweighted = Graph::new();
+    for v in &vertices {
+        reweighted.create_vertex(v);
+        for (dest, weight) in graph.neighbors(*v)? {
+            reweighted.add_edge(*v,dest,weight + potential[v] - potential[&dest]);
+        }
+    }
+    debug!("Reweighted graph has negative edges: {}",reweighted.has_negative_edges());
+
+    for (i, src) in vertices.iter().enumerate() {
+        let paths = Dijkstra::new().shortest_paths(&reweighted,*src)?;
+        for (dest, distance) in paths.distance {
+            let j = matrix.index_of(dest).unwrap();
+            // undo the reweighting to get the real distance
+            matrix.distance[i][j] = Some(distance + potential[&dest] - potential[src]);
+        }
+    }
+    Ok(matrix)
+}
+
+
+// picks Floyd-Warshall when the graph is dense enough that V^3 beats V * E log V
+pub fn all_pairs_shortest_paths<W: Weight, G: GraphView<Weight=W>>(graph: &G) -> Result<DistanceMatrix<W>> {
+    let vertex_count = graph.vertex_count();
+    let log_v = (usize::BITS - vertex_count.leading_zeros()) as usize;
+    // undirected edges are listed from both ends but only count once
+    let listed : usize = graph.vertices().map(|v| graph.neighbors(v).map_or(0,|edges| edges.count())).sum();
+    let edge_count = if graph.is_directed() { listed } else { listed / 2 };
+    if edge_count * log_v >= vertex_count * vertex_count {
+        floyd_warshall(graph)
+    }
+    else {
+        johnson(graph)
+    }
+}
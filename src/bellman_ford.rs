@@ -0,0 +1,108 @@
+use std::collections::BTreeMap;
+use crate::graph::GraphView;
+use crate::weight::Weight;
+use crate::paths::{ShortestPaths,NegativeCycle};
+use crate::error::{Error,Result};
+use log::{info,debug};
+
+
+pub struct BellmanFord<W = i32> {
+    pub distance : BTreeMap::<usize,W>,
+    pub predecessor : BTreeMap::<usize,usize>,
+}
+
+
+impl<W: Weight> Default for BellmanFord<W> {
+    fn default() -> Self {
+        BellmanFord::new()
+    }
+}
+
+impl<W: Weight> BellmanFord<W> {
+
+    pub fn new() -> Self {
+        BellmanFord {
+            distance : BTreeMap::<usize,W>::new(),
+            predecessor : BTreeMap::<usize,usize>::new(),
+        }
+    }
+
+    fn reset(&mut self) {
+        self.distance.clear();
+        self.predecessor.clear();
+    }
+
+    // relax every edge once, returning the last vertex whose distance improved (if any)
+    fn relax_edges(&mut self, edges: &[(usize,usize,W)]) -> Option<usize> {
+        let mut updated = None;
+        for (src,dest,weight) in edges {
+            if let Some(src_distance) = self.distance.get(src) {
+                let new_distance = *src_distance + *weight;
+                if self.distance.get(dest).is_none_or(|cur| new_distance < *cur) {
+                    self.distance.insert(*dest,new_distance);
+                    self.predecessor.insert(*dest,*src);
+                    updated = Some(*dest);
+                }
+            }
+        }
+        updated
+    }
+
+    // vertex was still improving after |V| - 1 rounds, so following its predecessors
+    // back |V| times guarantees we end up on the negative cycle itself
+    fn find_cycle(&self, vertex: usize, vertex_count: usize) -> NegativeCycle {
+        let mut cur = vertex;
+        for _ in 0..vertex_count {
+            cur = self.predecessor[&cur];
+        }
+
+        let start = cur;
+        let mut cycle = vec!(start);
+        cur = self.predecessor[&start];
+        while cur != start {
+            cycle.push(cur);
+            cur = self.predecessor[&cur];
+        }
+        cycle.push(start);
+        cycle.reverse();
+        NegativeCycle { vertices: cycle }
+    }
+
+    // shortest paths that allow negative edge weights, reporting the negative cycle
+    // instead of distances if one is reachable from the starting vertex
+    pub fn shortest_paths<G: GraphView<Weight=W>>(&mut self, graph: &G, starting_vertex: usize) -> Result<ShortestPaths<W>> {
+        info!("Starting Bellman-Ford shortest path with {}",starting_vertex);
+        let mut paths = ShortestPaths::new(starting_vertex);
+
+        if !graph.contains_vertex(starting_vertex) {
+            return Err(Error::InvalidStartVertex(starting_vertex));
+        }
+        self.reset();
+
+        let mut edges = Vec::<(usize,usize,W)>::new();
+        for v in graph.vertices() {
+            edges.extend(graph.neighbors(v)?.map(|(dest, weight)| (v,dest,weight)));
+        }
+
+        let vertex_count = graph.vertex_count();
+        self.distance.insert(starting_vertex,W::ZERO);
+        for round in 1..vertex_count {
+            if self.relax_edges(&edges).is_none() {
+                debug!("Bellman-Ford converged after {} rounds",round);
+                break;
+            }
+        }
+
+        // any improvement after |V| - 1 rounds means there is a negative cycle
+        if let Some(vertex) = self.relax_edges(&edges) {
+            let cycle = self.find_cycle(vertex,vertex_count);
+            debug!("Negative cycle found: {:?}",cycle.vertices);
+            return Err(Error::NegativeCycle(cycle));
+        }
+
+        paths.distance = self.distance.clone();
+        paths.predecessor = self.predecessor.clone();
+        Ok(paths)
+    }
+
+}
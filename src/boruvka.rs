@@ -0,0 +1,145 @@
+use std::thread;
+use crate::graph::GraphView;
+use crate::weight::Weight;
+use crate::spanning_tree::{SpanningTree,TreeEdge,TreeKind};
+use crate::union_find::UnionFind;
+use crate::error::{Error,Result};
+use log::{info,debug};
+
+
+pub struct Boruvka {
+    // number of threads used to scan the edges each round (1 = single threaded)
+    pub threads : usize,
+    pub kind : TreeKind,
+}
+
+
+impl Default for Boruvka {
+    fn default() -> Self {
+        Boruvka::new()
+    }
+}
+
+impl Boruvka {
+
+    pub fn new() -> Self {
+        Boruvka {
+            threads : 1,
+            kind : TreeKind::Minimum,
+        }
+    }
+
+    pub fn with_threads(threads: usize) -> Self {
+        Boruvka {
+            threads : threads.max(1),
+            kind : TreeKind::Minimum,
+        }
+    }
+
+    // true if edge a is a better choice than edge b
+    fn better<W: Weight>(&self, a: &(W,usize,usize), b: &(W,usize,usize)) -> bool {
+        self.kind.compare_edges(a,b).is_lt()
+    }
+
+    // find the cheapest edge leaving each component among a slice of the edge list,
+    // returned as indexes into the full list
+    fn cheapest_edges<W: Weight>(&self, edges: &[(W,usize,usize)], offset: usize, component: &[usize]) -> Vec<Option<usize>> {
+        let mut cheapest : Vec<Option<usize>> = vec![None; component.len()];
+        for (index, edge) in edges.iter().enumerate() {
+            let (c1, c2) = (component[edge.1], component[edge.2]);
+            if c1 == c2 {
+                continue;
+            }
+            for c in [c1,c2] {
+                if cheapest[c].is_none_or(|cur| self.better(edge,&edges[cur - offset])) {
+                    cheapest[c] = Some(index + offset);
+                }
+            }
+        }
+        cheapest
+    }
+
+    // splits the edge list across the worker threads and merges their results
+    fn parallel_cheapest_edges<W: Weight>(&self, edges: &[(W,usize,usize)], component: &[usize]) -> Vec<Option<usize>> {
+        let chunk_size = edges.len().div_ceil(self.threads).max(1);
+        thread::scope(|scope| {
+            let workers : Vec<_> = edges.chunks(chunk_size).enumerate()
+                .map(|(i,chunk)| scope.spawn(move || self.cheapest_edges(chunk,i*chunk_size,component)))
+                .collect();
+
+            let mut cheapest : Vec<Option<usize>> = vec![None; component.len()];
+            for worker in workers {
+                let partial = worker.join().expect("Boruvka worker thread panicked");
+                for (c, index) in partial.into_iter().enumerate() {
+                    if let Some(index) = index {
+                        if cheapest[c].is_none_or(|cur| self.better(&edges[index],&edges[cur])) {
+                            cheapest[c] = Some(index);
+                        }
+                    }
+                }
+            }
+            cheapest
+        })
+    }
+
+    // each round every component picks its cheapest outgoing edge and all of those
+    // edges are added at once, roughly halving the component count per round
+    pub fn spanning_tree<G: GraphView>(&self, graph: &G) -> Result<SpanningTree<G::Weight>> {
+        info!("Starting Boruvka Min Span Tree with {} thread(s)",self.threads);
+
+        if graph.is_directed() {
+            return Err(Error::DirectedGraph);
+        }
+
+        // work with vertex indexes so components can be kept in a flat array. Indexes are
+        // in vertex order, so (low, high) compares the same way as the vertex ids
+        let vertexes : Vec<usize> = graph.vertices().collect();
+        let index_of = |v: usize| vertexes.binary_search(&v).unwrap();
+        let mut edges = Vec::<(G::Weight,usize,usize)>::new();
+        for v in &vertexes {
+            for (dest, weight) in graph.neighbors(*v)? {
+                // each undirected edge once, self loops can never be part of a tree
+                if dest > *v {
+                    edges.push((weight,index_of(*v),index_of(dest)));
+                }
+            }
+        }
+
+        let mut sets = UnionFind::new();
+        for i in 0..vertexes.len() {
+            sets.make_set(i);
+        }
+
+        let mut tree_edges = Vec::<TreeEdge<G::Weight>>::new();
+        let mut round = 0;
+        loop {
+            round += 1;
+            let component : Vec<usize> = (0..vertexes.len()).map(|i| sets.find(i).unwrap()).collect();
+            let cheapest = if self.threads > 1 {
+                self.parallel_cheapest_edges(&edges,&component)
+            }
+            else {
+                self.cheapest_edges(&edges,0,&component)
+            };
+
+            let mut added = 0;
+            for index in cheapest.into_iter().flatten() {
+                let (weight,v1,v2) = edges[index];
+                // two components may have picked the same edge
+                if sets.union(v1,v2) {
+                    tree_edges.push(TreeEdge::new(vertexes[v1],vertexes[v2],weight));
+                    added += 1;
+                }
+            }
+            debug!("Boruvka round {} added {} edges, {} components remain",round,added,sets.set_count());
+            if added == 0 {
+                break;
+            }
+        }
+
+        let tree = SpanningTree::from_edges(&vertexes,&tree_edges);
+        info!("Spanning forest has {} component(s) after {} rounds",tree.component_count(),round);
+        Ok(tree)
+    }
+
+}
@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+use std::io::BufRead;
+use crate::weight::Weight;
+use crate::spanning_tree::{SpanningTree,TreeKind};
+use crate::readers::{fields,parse_field,read_lines};
+use crate::error::{Error,Result};
+use log::info;
+
+
+// answers "what is the heaviest edge on the tree path between u and v" in O(log n)
+// using binary lifting over a minimum spanning forest. That edge's weight is the
+// minimax path weight between u and v in the original graph. Built from a maximum
+// spanning tree it gives the lightest edge instead, which is the widest path (the most
+// capacity that can get from u to v).
+pub struct BottleneckQuery<W = i32> {
+    kind: TreeKind,
+    // vertex id -> position in the tables below
+    index: HashMap<usize,usize>,
+    depth: Vec<usize>,
+    component: Vec<usize>,
+    // up[k][i] is the 2^k-th ancestor of i (roots are their own parent) and worst[k][i]
+    // the worst edge weight on the way up to it
+    up: Vec<Vec<usize>>,
+    worst: Vec<Vec<W>>,
+}
+
+impl<W: Weight> BottleneckQuery<W> {
+
+    // kind is the kind of tree that tree is
+    pub fn new(tree: &SpanningTree<W>, kind: TreeKind) -> Self {
+        let mut index = HashMap::<usize,usize>::new();
+        let mut component = Vec::<usize>::new();
+        for (c, tree_component) in tree.components.iter().enumerate() {
+            for v in &tree_component.vertices {
+                index.insert(*v,component.len());
+                component.push(c);
+            }
+        }
+
+        // the weight above a root is never read since no climb goes past a root.
+        let n = component.len();
+        let mut depth = vec![0; n];
+        let mut parent : Vec<usize> = (0..n).collect();
+        let mut weight = vec![W::ZERO; n];
+        for (v, (up, d)) in tree.ancestry() {
+            let i = index[&v];
+            depth[i] = d;
+            if let Some((p, w)) = up {
+                parent[i] = index[&p];
+                weight[i] = w;
+            }
+        }
+
+        let levels = (usize::BITS - n.max(1).leading_zeros()) as usize;
+        let mut up = vec!(parent);
+        let mut worst = vec!(weight);
+        for k in 1..levels {
+            let (prev_up, prev_worst) = (&up[k - 1],&worst[k - 1]);
+            let next_up : Vec<usize> = (0..n).map(|i| prev_up[prev_up[i]]).collect();
+            let next_worst : Vec<W> = (0..n).map(|i| worse(kind,prev_worst[i],prev_worst[prev_up[i]])).collect();
+            up.push(next_up);
+            worst.push(next_worst);
+        }
+        info!("Bottleneck query tables have {} levels for {} vertexes",levels,n);
+        BottleneckQuery { kind, index, depth, component, up, worst }
+    }
+
+    // the worst edge weight on the tree path between v1 and v2, None if they are the
+    // same vertex or in different components (no path)
+    pub fn query(&self, v1: usize, v2: usize) -> Result<Option<W>> {
+        let mut a = *self.index.get(&v1).ok_or(Error::UnknownVertex(v1))?;
+        let mut b = *self.index.get(&v2).ok_or(Error::UnknownVertex(v2))?;
+        if a == b || self.component[a] != self.component[b] {
+            return Ok(None);
+        }
+
+        let mut result : Option<W> = None;
+        let climb = |result: &mut Option<W>, v: &mut usize, k: usize| {
+            let w = self.worst[k][*v];
+            *result = Some(result.map_or(w,|r| worse(self.kind,r,w)));
+            *v = self.up[k][*v];
+        };
+
+        // bring the deeper end up to the same depth, then climb both until just below
+        // their lowest common ancestor
+        if self.depth[a] < self.depth[b] {
+            std::mem::swap(&mut a,&mut b);
+        }
+        let diff = self.depth[a] - self.depth[b];
+        for k in 0..self.up.len() {
+            if diff & (1 << k) != 0 {
+                climb(&mut result,&mut a,k);
+            }
+        }
+        if a == b {
+            return Ok(result);
+        }
+        for k in (0..self.up.len()).rev() {
+            if self.up[k][a] != self.up[k][b] {
+                climb(&mut result,&mut a,k);
+                climb(&mut result,&mut b,k);
+            }
+        }
+        climb(&mut result,&mut a,0);
+        climb(&mut result,&mut b,0);
+        Ok(result)
+    }
+
+}
+
+
+// the weight the tree likes least: the heavier for minimum trees, the lighter for maximum
+fn worse<W: Weight>(kind: TreeKind, a: W, b: W) -> W {
+    if kind.compare(&a,&b).is_gt() { a } else { b }
+}
+
+
+// reads "u v" vertex pairs, one per line. Blank lines and lines starting with # are
+// skipped.
+pub fn read_queries(reader: &mut dyn BufRead) -> Result<Vec<(usize,usize)>> {
+    let mut queries = Vec::<(usize,usize)>::new();
+    for line in read_lines(reader) {
+        let (line_num, line_data) = line?;
+        if line_data.trim().is_empty() || line_data.trim_start().starts_with('#') {
+            continue;
+        }
+        let fields = fields(&line_data,None);
+        let v1 = parse_field::<usize>(fields.first(),line_num,&line_data,"vertex")?;
+        let v2 = parse_field::<usize>(fields.get(1),line_num,&line_data,"vertex")?;
+        queries.push((v1,v2));
+    }
+    Ok(queries)
+}
@@ -0,0 +1,100 @@
+use std::collections::{BTreeMap,HashMap};
+use crate::weight::Weight;
+use crate::spanning_tree::{SpanningTree,TreeKind};
+use crate::union_find::UnionFind;
+use log::info;
+
+
+// one step of the dendrogram. Clusters 0..n are the single vertexes (in the order of
+// Clustering::vertices) and merge i creates cluster n + i, as in scipy's linkage matrix.
+#[derive(Debug,Clone,PartialEq)]
+pub struct ClusterMerge<W = i32> {
+    pub left: usize,
+    pub right: usize,
+    pub height: W,
+    // number of vertexes in the merged cluster
+    pub size: usize,
+}
+
+
+// single linkage clustering from a minimum spanning tree (or forest). Joining the tree
+// edges from lightest to heaviest merges clusters in the same order single linkage
+// would, so stopping k clusters short of the end is the same as removing the k - 1
+// heaviest tree edges.
+#[derive(Debug,Clone,Default)]
+pub struct Clustering<W = i32> {
+    pub vertices: Vec<usize>,
+    pub merges: Vec<ClusterMerge<W>>,
+}
+
+impl<W: Weight> Clustering<W> {
+
+    pub fn new(tree: &SpanningTree<W>) -> Self {
+        let mut vertices : Vec<usize> = tree.components.iter().flat_map(|c| c.vertices.iter().copied()).collect();
+        vertices.sort_unstable();
+        let index_of = |v: usize| vertices.binary_search(&v).unwrap();
+
+        let mut edges : Vec<(W,usize,usize)> = tree.edges.iter().map(|e| (e.weight,e.src.min(e.dest),e.src.max(e.dest))).collect();
+        edges.sort_by(|a, b| TreeKind::Minimum.compare_edges(a,b));
+
+        let mut sets = UnionFind::new();
+        // the cluster id and size each set's representative currently stands for
+        let mut cluster = HashMap::<usize,(usize,usize)>::new();
+        for i in 0..vertices.len() {
+            sets.make_set(i);
+            cluster.insert(i,(i,1));
+        }
+        let mut merges = Vec::<ClusterMerge<W>>::new();
+        for (height, v1, v2) in edges {
+            let (a, b) = (sets.find(index_of(v1)).unwrap(),sets.find(index_of(v2)).unwrap());
+            let ((left, left_size), (right, right_size)) = (cluster[&a],cluster[&b]);
+            sets.union(a,b);
+            let size = left_size + right_size;
+            cluster.insert(sets.find(a).unwrap(),(vertices.len() + merges.len(),size));
+            merges.push(ClusterMerge { left: left.min(right), right: left.max(right), height, size });
+        }
+        info!("Clustering {} vertexes with {} merges",vertices.len(),merges.len());
+        Clustering { vertices, merges }
+    }
+
+    // number of merges made before there are only k clusters left. A forest never gets
+    // fewer clusters than it has components.
+    fn merges_for(&self, k: usize) -> usize {
+        self.vertices.len().saturating_sub(k.max(1)).min(self.merges.len())
+    }
+
+    // the vertexes of each of the k clusters, sorted, with the clusters in order of
+    // their lowest vertex
+    pub fn clusters(&self, k: usize) -> Vec<Vec<usize>> {
+        let mut sets = UnionFind::new();
+        for v in &self.vertices {
+            sets.make_set(*v);
+        }
+        // merges are replayed with a vertex from each side, found through the leaves
+        let mut member = (0..self.vertices.len()).collect::<Vec<usize>>();
+        for m in &self.merges[..self.merges_for(k)] {
+            let (a, b) = (self.vertices[member[m.left]],self.vertices[member[m.right]]);
+            sets.union(a,b);
+            member.push(member[m.left]);
+        }
+
+        let mut clusters = BTreeMap::<usize,Vec<usize>>::new();
+        for v in &self.vertices {
+            clusters.entry(sets.find(*v).unwrap()).or_default().push(*v);
+        }
+        let mut clusters : Vec<Vec<usize>> = clusters.into_values().collect();
+        clusters.sort();
+        clusters
+    }
+
+    // the smallest distance between two of the k clusters, which single linkage makes
+    // as large as possible. None if the clusters are never joined (k is at least the
+    // number of components, or there is only one cluster).
+    pub fn spacing(&self, k: usize) -> Option<W> {
+        if k <= 1 {
+            return None;
+        }
+        self.merges.get(self.merges_for(k)).map(|m| m.height)
+    }
+
+}
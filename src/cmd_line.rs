@@ -1,12 +1,61 @@
 extern crate clap;
 
-use clap::{Arg, Command};
+use std::path::Path;
+use clap::{Arg, Command, ErrorKind};
 use log::debug;
+use prim::{InputFormat,ParallelEdgePolicy,SelfLoopPolicy,Weight};
+use prim::readers::CsvReader;
+use prim::export::ExportFormat;
+use prim::input::uncompressed_path;
+use prim::fast_parser::FastEdgeListReader;
+
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum MstAlgorithm {
+    Prim,
+    Kruskal,
+    Boruvka,
+}
+
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum Mode {
+    Mst,
+    Dijkstra,
+    BellmanFord,
+    AllPairs,
+}
+
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum WeightType {
+    I32,
+    I64,
+    U64,
+    F64,
+}
 
 #[derive(Debug)]
 pub struct CommandArgs  {
     pub filename: String,
     pub start_vertex: usize,
+    pub target_vertex: Option<usize>,
+    pub mode: Mode,
+    pub algorithm: MstAlgorithm,
+    pub threads: usize,
+    pub maximum: bool,
+    pub k_best: Option<usize>,
+    pub check_unique: bool,
+    pub bottleneck_queries: Option<String>,
+    pub clusters: Option<usize>,
+    pub dendrogram: bool,
+    pub directed: bool,
+    pub strict: bool,
+    pub format: Option<InputFormat>,
+    pub csv: CsvReader,
+    pub export: Option<(String,ExportFormat)>,
+    pub fast: Option<FastEdgeListReader>,
+    pub csr: bool,
+    pub weight_type: WeightType,
+    pub parallel_edges: ParallelEdgePolicy,
+    pub self_loops: SelfLoopPolicy,
 }
 
 impl CommandArgs  {
@@ -14,45 +63,324 @@ impl CommandArgs  {
         // basic app information
         let app = Command::new("prim")
             .version("1.0")
-            .about("Calculates MST using Prim Algo")
+            .about("Calculates MST using Prim Algo, or shortest paths using Dijkstra")
             .author("Marvin Mednick");
 
         // Define the name command line option
         let filename_option = Arg::new("file")
             .takes_value(true)
-            .help("Input file name")
+            .help("Input file name (- for stdin), may be gzip, zstd or xz compressed")
             .required(true);
 
         let starting_option = Arg::new("start")
             .takes_value(true)
+            .validator(vertex_id)
             .help("Starting Vertex")
             .required(true);
 
+        let target_option = Arg::new("target")
+            .takes_value(true)
+            .validator(vertex_id)
+            .help("Target vertex (dijkstra and bellman-ford modes only), prints the path to this vertex")
+            .required(false);
+
+        let mode_option = Arg::new("mode")
+            .short('m')
+            .long("mode")
+            .takes_value(true)
+            .possible_values(["mst", "dijkstra", "bellman-ford", "all-pairs"])
+            .default_value("mst")
+            .help("Calculate the MST, the shortest paths from the starting vertex or the shortest path between any pair");
+
+        let directed_option = Arg::new("directed")
+            .long("directed")
+            .takes_value(false)
+            .help("Treat each edge line as a single directed edge instead of an undirected edge");
+
+        let algorithm_option = Arg::new("algorithm")
+            .short('a')
+            .long("algorithm")
+            .takes_value(true)
+            .possible_values(["prim", "kruskal", "boruvka"])
+            .default_value("prim")
+            .help("Algorithm used to calculate the MST");
+
+        let maximum_option = Arg::new("maximum")
+            .long("maximum")
+            .takes_value(false)
+            .help("Calculate the maximum spanning tree instead of the minimum");
+
+        let k_best_option = Arg::new("k-best")
+            .short('k')
+            .long("k-best")
+            .takes_value(true)
+            .validator(positive_count)
+            .help("Print the k best distinct spanning trees in order of total weight (mst mode only)");
+
+        let check_unique_option = Arg::new("check-unique")
+            .long("check-unique")
+            .takes_value(false)
+            .conflicts_with("k-best")
+            .help("Report whether the spanning tree is unique, and which edges could be swapped if not");
+
+        let bottleneck_option = Arg::new("bottleneck-queries")
+            .long("bottleneck-queries")
+            .takes_value(true)
+            .help("File of \"u v\" lines, prints the heaviest tree edge between each pair (lightest with --maximum)");
+
+        let clusters_option = Arg::new("clusters")
+            .long("clusters")
+            .takes_value(true)
+            .conflicts_with("maximum")
+            .validator(positive_count)
+            .help("Split the vertexes into this many single linkage clusters and print them with their spacing");
+
+        let dendrogram_option = Arg::new("dendrogram")
+            .long("dendrogram")
+            .takes_value(false)
+            .conflicts_with("maximum")
+            .help("Print the single linkage merges as \"left right height size\" lines");
+
+        let threads_option = Arg::new("threads")
+            .short('t')
+            .long("threads")
+            .takes_value(true)
+            .default_value("1")
+            .validator(positive_count)
+            .help("Number of threads used by the boruvka algorithm");
+
+        let strict_option = Arg::new("strict")
+            .long("strict")
+            .takes_value(false)
+            .help("Fail instead of warning when the header counts don't match the file");
+
+        let format_option = Arg::new("format")
+            .short('f')
+            .long("format")
+            .takes_value(true)
+            .possible_values(["edges", "dimacs", "csv", "mtx", "adj"])
+            .help("Input file format (default is based on the file extension: .gr .csv .tsv .mtx .adj, otherwise edges)");
+
+        let csv_delimiter_option = Arg::new("csv-delimiter")
+            .long("csv-delimiter")
+            .takes_value(true)
+            .validator(csv_delimiter)
+            .help("Field delimiter for csv input (default is tab for .tsv files, comma otherwise)");
+
+        let csv_columns_option = Arg::new("csv-columns")
+            .long("csv-columns")
+            .takes_value(true)
+            .default_value("0,1,2")
+            .validator(csv_columns)
+            .help("Columns (starting at 0) holding src,dest[,weight] for csv input, edges without a weight column weigh 1");
+
+        let csv_header_option = Arg::new("csv-header")
+            .long("csv-header")
+            .takes_value(false)
+            .help("The first row of csv input is column names");
+
+        let export_option = Arg::new("export")
+            .long("export")
+            .takes_value(true)
+            .help("Write the graph (with the MST highlighted in mst mode) to this file");
+
+        let export_format_option = Arg::new("export-format")
+            .long("export-format")
+            .takes_value(true)
+            .possible_values(["dot", "graphml", "json"])
+            .help("Export file format (default is based on the file extension, otherwise dot)");
+
+        let fast_option = Arg::new("fast")
+            .long("fast")
+            .takes_value(false)
+            .help("Parse edge list input with the byte level parser, for very large files");
+
+        let parse_threads_option = Arg::new("parse-threads")
+            .long("parse-threads")
+            .takes_value(true)
+            .default_value("1")
+            .requires("fast")
+            .validator(positive_count)
+            .help("Number of threads used by the fast parser");
+
+        let no_mmap_option = Arg::new("no-mmap")
+            .long("no-mmap")
+            .takes_value(false)
+            .requires("fast")
+            .help("Read the file into memory instead of memory mapping it with the fast parser");
+
+        let csr_option = Arg::new("csr")
+            .long("csr")
+            .takes_value(false)
+            .conflicts_with("export")
+            .help("Load into a compact read only graph to save memory (mst, dijkstra and all-pairs modes only)");
+
+        let weight_type_option = Arg::new("weight-type")
+            .short('w')
+            .long("weight-type")
+            .takes_value(true)
+            .possible_values([i32::NAME, i64::NAME, u64::NAME, f64::NAME])
+            .default_value("i32")
+            .help("Type of the edge weights, f64 allows decimal weights (NaN is rejected)");
+
+        let parallel_edges_option = Arg::new("parallel-edges")
+            .long("parallel-edges")
+            .takes_value(true)
+            .possible_values(["keep-all", "keep-min", "keep-max", "sum", "reject"])
+            .default_value("keep-all")
+            .help("How to handle several edges between the same vertexes");
+
+        let self_loops_option = Arg::new("self-loops")
+            .long("self-loops")
+            .takes_value(true)
+            .possible_values(["keep", "drop", "reject"])
+            .default_value("keep")
+            .help("How to handle edges from a vertex to itself");
+
         // now add in the argument we want to parse
         let mut app = app.arg(filename_option);
         app = app.arg(starting_option);
+        app = app.arg(target_option);
+        app = app.arg(mode_option);
+        app = app.arg(algorithm_option);
+        app = app.arg(maximum_option);
+        app = app.arg(k_best_option);
+        app = app.arg(check_unique_option);
+        app = app.arg(bottleneck_option);
+        app = app.arg(clusters_option);
+        app = app.arg(dendrogram_option);
+        app = app.arg(threads_option);
+        app = app.arg(directed_option);
+        app = app.arg(strict_option);
+        app = app.arg(format_option);
+        app = app.arg(csv_delimiter_option);
+        app = app.arg(csv_columns_option);
+        app = app.arg(csv_header_option);
+        app = app.arg(export_option);
+        app = app.arg(export_format_option);
+        app = app.arg(fast_option);
+        app = app.arg(parse_threads_option);
+        app = app.arg(no_mmap_option);
+        app = app.arg(csr_option);
+        app = app.arg(weight_type_option);
+        app = app.arg(parallel_edges_option);
+        app = app.arg(self_loops_option);
 
         // extract the matches
-        let matches = app.get_matches();
+        let matches = app.get_matches_mut();
 
         // Extract the actual name
         let filename = matches.value_of("file")
             .expect("Filename can't be None, we said it was required");
 
-        let num_str = matches.value_of("start");
+        let start = matches.value_of_t::<usize>("start").unwrap();
 
-        let start = match num_str {
-            None => { println!("Start is None..."); 0},
-            Some(s) => {
-                match s.parse::<usize>() {
-                    Ok(n) => n,
-                    Err(_) => {println!("That's not a number! {}", s); 0},
-                }
-            }
+        let target = matches.value_of_t::<usize>("target").ok();
+
+        let mode = match matches.value_of("mode") {
+            Some("dijkstra") => Mode::Dijkstra,
+            Some("bellman-ford") => Mode::BellmanFord,
+            Some("all-pairs") => Mode::AllPairs,
+            _ => Mode::Mst,
+        };
+        // the other modes have no single path to print
+        if target.is_some() && mode != Mode::Dijkstra && mode != Mode::BellmanFord {
+            app.error(ErrorKind::ArgumentConflict,"a target vertex can only be given in the dijkstra and bellman-ford modes").exit();
+        }
+
+        let algorithm = match matches.value_of("algorithm") {
+            Some("kruskal") => MstAlgorithm::Kruskal,
+            Some("boruvka") => MstAlgorithm::Boruvka,
+            _ => MstAlgorithm::Prim,
         };
 
-        debug!("clap args: {} {}",filename, start);
+        let threads = matches.value_of_t::<usize>("threads").unwrap();
+
+        let maximum = matches.is_present("maximum");
+        let k_best = matches.value_of_t::<usize>("k-best").ok();
+
+        let check_unique = matches.is_present("check-unique");
+        let bottleneck_queries = matches.value_of("bottleneck-queries").map(|f| f.to_string());
+        let clusters = matches.value_of_t::<usize>("clusters").ok();
+        let dendrogram = matches.is_present("dendrogram");
+
+        let directed = matches.is_present("directed");
+        let strict = matches.is_present("strict");
+
+        let format = matches.value_of("format").and_then(InputFormat::from_name);
+
+        let delimiter = match matches.value_of("csv-delimiter") {
+            Some(d) => csv_delimiter(d).unwrap(),
+            None if uncompressed_path(Path::new(filename)).extension().is_some_and(|e| e == "tsv") => '\t',
+            None => ',',
+        };
+        let mut csv = CsvReader::new(delimiter);
+        csv.has_header = matches.is_present("csv-header");
+        (csv.src_column, csv.dest_column, csv.weight_column) = csv_columns(matches.value_of("csv-columns").unwrap()).unwrap();
 
-        CommandArgs { filename: filename.to_string(), start_vertex : start}
+        let export = matches.value_of("export").map(|export_file| {
+            let export_format = matches.value_of("export-format").and_then(ExportFormat::from_name)
+                .or_else(|| ExportFormat::from_path(Path::new(export_file)))
+                .unwrap_or(ExportFormat::Dot);
+            (export_file.to_string(), export_format)
+        });
+
+        let fast = if matches.is_present("fast") {
+            let parse_threads = matches.value_of_t::<usize>("parse-threads").unwrap();
+            Some(FastEdgeListReader::new(parse_threads,!matches.is_present("no-mmap")))
+        }
+        else {
+            None
+        };
+
+        let csr = matches.is_present("csr");
+
+        let weight_type = match matches.value_of("weight-type") {
+            Some(i64::NAME) => WeightType::I64,
+            Some(u64::NAME) => WeightType::U64,
+            Some(f64::NAME) => WeightType::F64,
+            _ => WeightType::I32,
+        };
+
+        let parallel_edges = matches.value_of("parallel-edges").and_then(ParallelEdgePolicy::from_name).unwrap_or_default();
+        let self_loops = matches.value_of("self-loops").and_then(SelfLoopPolicy::from_name).unwrap_or_default();
+
+        debug!("clap args: {} {} {:?} {:?}",filename, start, mode, algorithm);
+
+        CommandArgs { filename: filename.to_string(), start_vertex : start, target_vertex : target, mode, algorithm, threads, maximum, k_best, check_unique, bottleneck_queries, clusters, dendrogram, directed, strict, format, csv, export, fast, csr, weight_type, parallel_edges, self_loops }
     }   
 }
+
+
+// clap validators, so a bad value stops with a usage error instead of being replaced
+// by a default
+
+fn vertex_id(value: &str) -> Result<usize,String> {
+    value.parse::<usize>().map_err(|_| format!("{} isn't a vertex number",value))
+}
+
+fn positive_count(value: &str) -> Result<usize,String> {
+    match value.parse::<usize>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(format!("{} isn't a whole number above 0",value)),
+    }
+}
+
+fn csv_delimiter(value: &str) -> Result<char,String> {
+    match value {
+        "\\t" | "tab" => Ok('\t'),
+        d if d.chars().count() == 1 => Ok(d.chars().next().unwrap()),
+        _ => Err(format!("{} isn't a single character or tab",value)),
+    }
+}
+
+// src,dest[,weight]
+fn csv_columns(value: &str) -> Result<(usize,usize,Option<usize>),String> {
+    let columns : Vec<usize> = value.split(',').map(|c| c.trim().parse::<usize>()).collect::<std::result::Result<_,_>>()
+        .map_err(|_| format!("{} isn't a list of column numbers",value))?;
+    match columns[..] {
+        [src, dest] => Ok((src,dest,None)),
+        [src, dest, weight] => Ok((src,dest,Some(weight))),
+        _ => Err(format!("{} doesn't name 2 or 3 columns",value)),
+    }
+}
@@ -0,0 +1,204 @@
+use std::mem::size_of;
+use log::info;
+use crate::graph::{Graph,GraphView};
+use crate::error::{Error,Result};
+use crate::loader::LoadOptions;
+use crate::readers::{check_header,EdgeSink};
+use crate::weight::Weight;
+use crate::edge_policy::{EdgeMerger,Merge};
+
+
+// immutable compressed sparse row graph - every edge is a target index and weight
+// stored in flat arrays, grouped by source vertex. This takes a fraction of the
+// memory of Graph (no per vertex BTreeMaps, no incoming lists) but can't be changed
+// once built.
+#[derive(Debug,Clone,Default)]
+pub struct CsrGraph<W = i32> {
+    // vertex ids in increasing order, a vertex's position here is its index
+    vertex_ids: Vec<usize>,
+    // the edges leaving vertex index i are at offsets[i]..offsets[i + 1]
+    offsets: Vec<usize>,
+    // target vertex index of each edge
+    targets: Vec<usize>,
+    weights: Vec<W>,
+    // undirected edges are stored from both ends but only counted once
+    edge_count: usize,
+    directed: bool,
+}
+
+impl<W: Weight> CsrGraph<W> {
+
+    pub fn from_graph(graph: &Graph<W>) -> Self {
+        let vertex_ids = graph.get_vertexes();
+        let mut offsets = Vec::<usize>::with_capacity(vertex_ids.len() + 1);
+        let mut targets = Vec::<usize>::with_capacity(graph.edge_count());
+        let mut weights = Vec::<W>::with_capacity(graph.edge_count());
+
+        offsets.push(0);
+        for v in &vertex_ids {
+            // every id came from the graph, so the lookup can't fail. Parallel copies of
+            // an edge each get an entry, as CsrBuilder gives them.
+            for (edge, count) in graph.get_outgoing_counts(*v).unwrap() {
+                let target = vertex_ids.binary_search(&edge.vertex).unwrap();
+                for _ in 0..count {
+                    targets.push(target);
+                    weights.push(edge.weight);
+                }
+            }
+            offsets.push(targets.len());
+        }
+        CsrGraph { vertex_ids, offsets, targets, weights, edge_count: graph.edge_count(), directed: graph.is_directed() }
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+
+    pub fn index_of(&self, vertex: usize) -> Option<usize> {
+        self.vertex_ids.binary_search(&vertex).ok()
+    }
+
+    pub fn vertex_id(&self, index: usize) -> usize {
+        self.vertex_ids[index]
+    }
+
+    // (target index, weight) of each edge leaving vertex index
+    pub fn edges(&self, index: usize) -> impl Iterator<Item=(usize,W)> + '_ {
+        let range = self.offsets[index]..self.offsets[index + 1];
+        self.targets[range.clone()].iter().copied().zip(self.weights[range].iter().copied())
+    }
+
+    // approximate heap memory used by the arrays, in bytes
+    pub fn memory_size(&self) -> usize {
+        (self.vertex_ids.len() + self.offsets.len() + self.targets.len()) * size_of::<usize>()
+            + self.weights.len() * size_of::<W>()
+    }
+
+}
+
+impl<W: Weight> GraphView for CsrGraph<W> {
+    type Weight = W;
+
+    fn vertex_count(&self) -> usize {
+        self.vertex_ids.len()
+    }
+
+    fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    fn vertices(&self) -> impl Iterator<Item=usize> + '_ {
+        self.vertex_ids.iter().copied()
+    }
+
+    fn contains_vertex(&self, vertex: usize) -> bool {
+        self.index_of(vertex).is_some()
+    }
+
+    fn neighbors(&self, vertex: usize) -> Result<impl Iterator<Item=(usize,W)> + '_> {
+        let index = self.index_of(vertex).ok_or(Error::UnknownVertex(vertex))?;
+        Ok(self.edges(index).map(|(target, weight)| (self.vertex_ids[target],weight)))
+    }
+
+    fn has_negative_edges(&self) -> bool {
+        self.weights.iter().any(|w| *w < W::ZERO)
+    }
+
+}
+
+
+// collects edges as they are read and packs them into a CsrGraph at the end, so a
+// large file never has to be held as a Graph
+pub struct CsrBuilder<'a, W = i32> {
+    options: &'a LoadOptions,
+    vertexes: Vec<usize>,
+    // (src, dest, weight) for each edge kept, before undirected edges are doubled
+    edges: Vec<(usize,usize,W)>,
+    merger: EdgeMerger<W>,
+    // edges read, including any merged or dropped
+    read: usize,
+}
+
+impl<'a, W: Weight> CsrBuilder<'a, W> {
+
+    pub fn new(options: &'a LoadOptions) -> Self {
+        let merger = EdgeMerger::new(options.parallel_edges,options.self_loops,options.directed);
+        CsrBuilder { options, vertexes: Vec::<usize>::new(), edges: Vec::<(usize,usize,W)>::new(), merger, read: 0 }
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.read
+    }
+
+    // checks the header as GraphBuilder::finish does, then packs the edges kept
+    pub fn finish(mut self, header: Option<(usize,usize)>) -> Result<CsrGraph<W>> {
+        info!("Loaded {} edges",self.read);
+        self.merger.log_counts();
+
+        let mut vertex_ids = std::mem::take(&mut self.vertexes);
+        vertex_ids.extend(self.edges.iter().flat_map(|(src, dest, _)| [*src,*dest]));
+        vertex_ids.sort_unstable();
+        vertex_ids.dedup();
+        check_header(self.options,header,vertex_ids.len(),self.read)?;
+
+        // undirected graphs store each edge from both ends (self loops once), as Graph does
+        let directed = self.options.directed;
+        let edge_count = self.edges.len();
+        let index_of = |v: usize| vertex_ids.binary_search(&v).unwrap();
+        let mut offsets = vec![0; vertex_ids.len() + 1];
+        for (src, dest, _) in &self.edges {
+            offsets[index_of(*src) + 1] += 1;
+            if !directed && src != dest {
+                offsets[index_of(*dest) + 1] += 1;
+            }
+        }
+        for i in 1..offsets.len() {
+            offsets[i] += offsets[i - 1];
+        }
+
+        // fill each vertex's range, then sort it to match the order Graph returns edges in
+        let total = offsets[vertex_ids.len()];
+        let mut next = offsets.clone();
+        let mut slots = vec![(0,W::ZERO); total];
+        for (src, dest, weight) in self.edges.drain(..) {
+            let (s, d) = (index_of(src),index_of(dest));
+            slots[next[s]] = (d,weight);
+            next[s] += 1;
+            if !directed && s != d {
+                slots[next[d]] = (s,weight);
+                next[d] += 1;
+            }
+        }
+        for i in 0..vertex_ids.len() {
+            slots[offsets[i]..offsets[i + 1]].sort_unstable_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)));
+        }
+
+        let (targets, weights) = slots.into_iter().unzip();
+        let graph = CsrGraph { vertex_ids, offsets, targets, weights, edge_count, directed };
+        info!("CSR graph has {} vertexes and {} edges in {} bytes",graph.vertex_ids.len(),graph.edge_count(),graph.memory_size());
+        Ok(graph)
+    }
+
+}
+
+impl<W: Weight> EdgeSink<W> for CsrBuilder<'_, W> {
+
+    fn add_vertex(&mut self, vertex: usize) {
+        self.vertexes.push(vertex);
+    }
+
+    fn add_edge(&mut self, src: usize, dest: usize, weight: W) -> Result<()> {
+        self.read += 1;
+        self.add_implied_edge(src,dest,weight)
+    }
+
+    fn add_implied_edge(&mut self, src: usize, dest: usize, weight: W) -> Result<()> {
+        match self.merger.add(src,dest,weight,self.edges.len())? {
+            Merge::Add => self.edges.push((src,dest,weight)),
+            Merge::Replace { slot, new, .. } => self.edges[slot].2 = new,
+            Merge::Skip => (),
+        }
+        Ok(())
+    }
+
+}
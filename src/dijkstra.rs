@@ -2,78 +2,132 @@ use std::collections::{HashMap};
 
 extern crate minheap;
 use minheap::MinHeap;
-use crate::graph::Graph;
-
-
-pub struct Dijkstra {
-    pub unprocessed_vertex : MinHeap::<i32>,
-    pub processed_vertex : HashMap::<u32,i32>,
+use crate::graph::GraphView;
+use crate::weight::Weight;
+use crate::paths::ShortestPaths;
+use crate::error::{Error,Result};
+use log::{info,warn};
+
+
+pub struct Dijkstra<W = i32> {
+    pub unprocessed_vertex : MinHeap::<W>,
+    pub processed_vertex : HashMap::<usize,W>,
+    // vertex that currently offers the shortest path to each vertex
+    pub predecessor : HashMap::<usize,usize>,
 }
             
 
-impl Dijkstra {
+impl<W: Weight> Default for Dijkstra<W> {
+    fn default() -> Self {
+        Dijkstra::new()
+    }
+}
+
+impl<W: Weight> Dijkstra<W> {
 
     pub fn new() -> Self {
 
         Dijkstra  {
-            unprocessed_vertex : MinHeap::<i32>::new(),
-            processed_vertex : HashMap::<u32,i32>::new(),
+            unprocessed_vertex : MinHeap::<W>::new(),
+            processed_vertex : HashMap::<usize,W>::new(),
+            predecessor : HashMap::<usize,usize>::new(),
         }
     }
 
 
+    // forget the previous search so the same instance can run another one
+    fn reset(&mut self) {
+        self.unprocessed_vertex = MinHeap::<W>::new();
+        self.processed_vertex.clear();
+        self.predecessor.clear();
+    }
+
     // update scoring for dijkstra shortest path
     
-    pub fn update_scoring(&mut self, graph: &mut Graph, id: u32) {
-   //   get a vector of outgoing edges (vertex, weight..)
-        let adj_vertexes = graph.get_outgoing(id);
-        
+    pub fn update_scoring<G: GraphView<Weight=W>>(&mut self, graph: &G, id: usize) -> Result<()> {
         // get the distance/score from the current vertex as the base
-        let cur_vertex_distance = self.processed_vertex.get(&id).unwrap().clone();
+        let cur_vertex_distance = self.processed_vertex[&id];
 
-        // update each of this nodes adjancent vertexes, if the new distance
+        // update each of this nodes adjancent vertexes (vertex, weight..), if the new distance
         // is < the current distance
-        for v in adj_vertexes {
-  //          println!("Dijsktra updating adjacent {:?}",v);
+        for (vertex, weight) in graph.neighbors(id)? {
+  //          println!("Dijsktra updating adjacent {:?}",vertex);
             // if the adjacent vertex is still in the unprocessed list, then 
             // update the scoring, otherwise skip it (since its already in the processed list)
-            if let Some(cur_score) = self.unprocessed_vertex.peek_id_data(v.vertex) {
-                let new_score = cur_vertex_distance + v.weight;
+            let new_score = cur_vertex_distance + weight;
+            if let Some(cur_score) = self.unprocessed_vertex.peek_id_data(vertex) {
                 if new_score < cur_score {
-//                    println!("Update scoring on {} from {} to {}",v.vertex,cur_score,new_score);
-                    let vertex_index = self.unprocessed_vertex.get_id_index(v.vertex).unwrap().clone();
+//                    println!("Update scoring on {} from {} to {}",vertex,cur_score,new_score);
+                    let vertex_index = *self.unprocessed_vertex.get_id_index(vertex).unwrap();
                     self.unprocessed_vertex.update(vertex_index,new_score);
+                    self.predecessor.insert(vertex,id);
  //                   println!("Unprocessed: {:?}",self.unprocessed_vertex)
                 }
              }       
+            else if !self.processed_vertex.contains_key(&vertex) {
+                // first path found to this vertex
+                self.unprocessed_vertex.insert(vertex,new_score);
+                self.predecessor.insert(vertex,id);
+            }
             
         }
+        Ok(())
 
     }
 
-    pub fn shortest_paths(&mut self, graph: &mut Graph, starting_vertex: u32) {
-        println!("Starting shortest path with {}",starting_vertex);
+    // processes vertexes in order of distance from the starting vertex, stopping
+    // early once target (if any) has been processed since its distance is then final
+    fn search<G: GraphView<Weight=W>>(&mut self, graph: &G, starting_vertex: usize, target: Option<usize>) -> Result<ShortestPaths<W>> {
+        let mut paths = ShortestPaths::new(starting_vertex);
+
+        if !graph.contains_vertex(starting_vertex) {
+            return Err(Error::InvalidStartVertex(starting_vertex));
+        }
 
-        if let Some(starting_index) = self.unprocessed_vertex.get_id_index(starting_vertex) {
+        // processed distances are final only if no edge can make a path shorter
+        if graph.has_negative_edges() {
+            warn!("Graph has negative edge weights, Dijkstra results may be incorrect (use Bellman-Ford)");
+        }
 
-            let index = starting_index.clone();
-            self.unprocessed_vertex.delete(index);
-            
-            // setup the initial distance for the starting vertex to 0 (to itself)
-            self.processed_vertex.insert(starting_vertex,0);
+        // setup the initial distance for the starting vertex to 0 (to itself)
+        self.reset();
+        self.processed_vertex.insert(starting_vertex,W::ZERO);
 
-            self.update_scoring(graph,starting_vertex);
+        if target != Some(starting_vertex) {
+            self.update_scoring(graph,starting_vertex)?;
+        }
 
-            while let Some((next_vertex,next_vertex_score)) = self.unprocessed_vertex.get_min_entry() {
- //               println!("Processing vertex {} score: {}",next_vertex,next_vertex_score);
-                self.processed_vertex.insert(next_vertex,next_vertex_score);
-                self.update_scoring(graph,next_vertex);
+        while let Some((next_vertex,next_vertex_score)) = self.unprocessed_vertex.get_min_entry() {
+ //           println!("Processing vertex {} score: {}",next_vertex,next_vertex_score);
+            self.processed_vertex.insert(next_vertex,next_vertex_score);
+            if target == Some(next_vertex) {
+                info!("Reached target {} with distance {}",next_vertex,next_vertex_score);
+                break;
             }
-         }       
-        else {
-            println!("Starting vertex {} is not in the graph",starting_vertex);
+            self.update_scoring(graph,next_vertex)?;
         }
 
+        paths.distance.extend(self.processed_vertex.iter().map(|(v,d)| (*v,*d)));
+        paths.predecessor.extend(self.predecessor.iter()
+                .filter(|(v,_)| self.processed_vertex.contains_key(v))
+                .map(|(v,p)| (*v,*p)));
+        Ok(paths)
+    }
+
+    pub fn shortest_paths<G: GraphView<Weight=W>>(&mut self, graph: &G, starting_vertex: usize) -> Result<ShortestPaths<W>> {
+        info!("Starting shortest path with {}",starting_vertex);
+        self.search(graph,starting_vertex,None)
+    }
+
+    // returns the vertexes along the shortest path from src to dst (inclusive)
+    // and its total weight, or None if dst can't be reached
+    pub fn shortest_path<G: GraphView<Weight=W>>(&mut self, graph: &G, src: usize, dst: usize) -> Result<Option<(Vec<usize>,W)>> {
+        info!("Starting shortest path from {} to {}",src,dst);
+        if !graph.contains_vertex(dst) {
+            return Err(Error::UnknownVertex(dst));
+        }
+        let paths = self.search(graph,src,Some(dst))?;
+        Ok(paths.path_to(dst).map(|path| (path,paths.distance[&dst])))
     }
 
 }
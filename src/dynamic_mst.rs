@@ -0,0 +1,208 @@
+use std::collections::{BTreeMap,HashMap,HashSet,VecDeque};
+use crate::graph::{Graph,GraphView};
+use crate::weight::Weight;
+use crate::kruskal::Kruskal;
+use crate::spanning_tree::{SpanningTree,TreeEdge,TreeKind};
+use crate::error::Result;
+use log::debug;
+
+
+// how the tree changed after an edge was inserted or deleted. Edges are recorded as
+// (low vertex, high vertex).
+#[derive(Debug,Clone,PartialEq)]
+pub struct MstDelta<W = i32> {
+    pub added: Vec<TreeEdge<W>>,
+    pub removed: Vec<TreeEdge<W>>,
+    pub old_weight: W,
+    pub new_weight: W,
+}
+
+impl<W: Weight> MstDelta<W> {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+
+// keeps a minimum (or maximum) spanning forest up to date as edges are added to and
+// deleted from the graph, instead of rebuilding it after every change. An insert only
+// has to look at the tree path between the edge's ends (the cycle it closes), and
+// deleting a tree edge only has to look at the edges leaving the smaller of the two
+// pieces it leaves behind.
+pub struct DynamicMst<W = i32> {
+    graph: Graph<W>,
+    // tree edges from both ends
+    tree: BTreeMap<usize,Vec<(usize,W)>>,
+    total_weight: W,
+    pub kind: TreeKind,
+}
+
+impl<W: Weight> DynamicMst<W> {
+
+    pub fn new(graph: Graph<W>, kind: TreeKind) -> Result<Self> {
+        let start = Kruskal { kind }.spanning_tree(&graph)?;
+        let mut tree = BTreeMap::<usize,Vec<(usize,W)>>::new();
+        for v in graph.vertices() {
+            tree.insert(v,Vec::<(usize,W)>::new());
+        }
+        let mut mst = DynamicMst { graph, tree, total_weight: W::ZERO, kind };
+        for e in &start.edges {
+            mst.link(e.src,e.dest,e.weight);
+        }
+        Ok(mst)
+    }
+
+    pub fn graph(&self) -> &Graph<W> {
+        &self.graph
+    }
+
+    pub fn total_weight(&self) -> W {
+        self.total_weight
+    }
+
+    pub fn spanning_tree(&self) -> SpanningTree<W> {
+        let vertexes : Vec<usize> = self.tree.keys().copied().collect();
+        let edges : Vec<TreeEdge<W>> = self.tree.iter()
+            .flat_map(|(v, adjacent)| adjacent.iter().filter(move |(n, _)| v < n).map(move |(n, w)| TreeEdge::new(*v,*n,*w)))
+            .collect();
+        SpanningTree::from_edges(&vertexes,&edges)
+    }
+
+    // adds an edge to the graph. If it closes a cycle in the tree it replaces the worst
+    // edge on that cycle when it is better, otherwise it joins two trees of the forest.
+    pub fn insert_edge(&mut self, v1: usize, v2: usize, weight: W) -> Result<MstDelta<W>> {
+        let old_weight = self.total_weight;
+        self.graph.add_edge(v1,v2,weight);
+        for v in [v1,v2] {
+            self.tree.entry(v).or_default();
+        }
+        let mut delta = MstDelta { added: Vec::<TreeEdge<W>>::new(), removed: Vec::<TreeEdge<W>>::new(), old_weight, new_weight: old_weight };
+        if v1 == v2 {
+            return Ok(delta);
+        }
+
+        match self.tree_path(v1,v2) {
+            None => {
+                self.link(v1,v2,weight);
+                delta.added.push(edge(v1,v2,weight));
+            },
+            Some(path) => {
+                let worst = path.into_iter().max_by(|a, b| self.compare(a,b)).unwrap();
+                if self.compare(&edge(v1,v2,weight),&worst).is_lt() {
+                    self.cut(worst.src,worst.dest,worst.weight);
+                    self.link(v1,v2,weight);
+                    delta.removed.push(worst);
+                    delta.added.push(edge(v1,v2,weight));
+                }
+            },
+        }
+        delta.new_weight = self.total_weight;
+        debug!("Inserted {} - {} wgt: {}, tree weight {} -> {}",v1,v2,weight,old_weight,delta.new_weight);
+        Ok(delta)
+    }
+
+    // deletes an edge from the graph. When it was a tree edge, the best edge across the
+    // cut it leaves (if any) takes its place.
+    pub fn delete_edge(&mut self, v1: usize, v2: usize, weight: W) -> Result<MstDelta<W>> {
+        let old_weight = self.total_weight;
+        self.graph.delete_edge(v1,v2,weight)?;
+        let mut delta = MstDelta { added: Vec::<TreeEdge<W>>::new(), removed: Vec::<TreeEdge<W>>::new(), old_weight, new_weight: old_weight };
+
+        // a parallel copy of the same edge can stay in the tree in its place
+        let in_tree = self.tree[&v1].iter().any(|(n, w)| *n == v2 && w.total_cmp(&weight).is_eq());
+        let copy_left = self.graph.neighbors(v1)?.any(|(n, w)| n == v2 && w.total_cmp(&weight).is_eq());
+        if v1 == v2 || !in_tree || copy_left {
+            return Ok(delta);
+        }
+
+        self.cut(v1,v2,weight);
+        delta.removed.push(edge(v1,v2,weight));
+
+        // search from the smaller piece, any edge leaving it reaches the other piece
+        let side = self.smaller_side(v1,v2);
+        let mut best : Option<TreeEdge<W>> = None;
+        for v in &side {
+            for (n, w) in self.graph.neighbors(*v)? {
+                let candidate = edge(*v,n,w);
+                if !side.contains(&n) && best.as_ref().is_none_or(|b| self.compare(&candidate,b).is_lt()) {
+                    best = Some(candidate);
+                }
+            }
+        }
+        if let Some(e) = best {
+            self.link(e.src,e.dest,e.weight);
+            delta.added.push(e);
+        }
+        delta.new_weight = self.total_weight;
+        debug!("Deleted {} - {} wgt: {}, tree weight {} -> {}",v1,v2,weight,old_weight,delta.new_weight);
+        Ok(delta)
+    }
+
+    // tree edges here are always (low, high), see edge()
+    fn compare(&self, a: &TreeEdge<W>, b: &TreeEdge<W>) -> std::cmp::Ordering {
+        self.kind.compare_edges(&(a.weight,a.src,a.dest),&(b.weight,b.src,b.dest))
+    }
+
+    fn link(&mut self, v1: usize, v2: usize, weight: W) {
+        self.tree.entry(v1).or_default().push((v2,weight));
+        self.tree.entry(v2).or_default().push((v1,weight));
+        self.total_weight = self.total_weight + weight;
+    }
+
+    fn cut(&mut self, v1: usize, v2: usize, weight: W) {
+        for (a, b) in [(v1,v2),(v2,v1)] {
+            let adjacent = self.tree.get_mut(&a).unwrap();
+            let i = adjacent.iter().position(|(n, w)| *n == b && w.total_cmp(&weight).is_eq()).unwrap();
+            adjacent.swap_remove(i);
+        }
+        self.total_weight = self.total_weight - weight;
+    }
+
+    // the tree edges between v1 and v2, or None if they are in different trees
+    fn tree_path(&self, v1: usize, v2: usize) -> Option<Vec<TreeEdge<W>>> {
+        let mut parent = HashMap::<usize,(usize,W)>::new();
+        let mut queue = VecDeque::<usize>::from(vec!(v1));
+        while let Some(v) = queue.pop_front() {
+            if v == v2 {
+                let mut path = Vec::<TreeEdge<W>>::new();
+                let mut cur = v2;
+                while cur != v1 {
+                    let (p, w) = parent[&cur];
+                    path.push(edge(p,cur,w));
+                    cur = p;
+                }
+                return Some(path);
+            }
+            for (n, w) in &self.tree[&v] {
+                if *n != v1 && !parent.contains_key(n) {
+                    parent.insert(*n,(v,*w));
+                    queue.push_back(*n);
+                }
+            }
+        }
+        None
+    }
+
+    // the vertexes of whichever of v1's and v2's trees is smaller. Both are searched a
+    // vertex at a time in turn, so the search stops once the smaller one is exhausted
+    // instead of walking all of the larger one.
+    fn smaller_side(&self, v1: usize, v2: usize) -> HashSet<usize> {
+        let mut searches = [v1,v2].map(|v| (HashSet::<usize>::from([v]),VecDeque::<usize>::from(vec!(v))));
+        loop {
+            for (seen, queue) in searches.iter_mut() {
+                let Some(v) = queue.pop_front() else { return std::mem::take(seen) };
+                for (n, _) in &self.tree[&v] {
+                    if seen.insert(*n) {
+                        queue.push_back(*n);
+                    }
+                }
+            }
+        }
+    }
+
+}
+
+
+fn edge<W: Weight>(v1: usize, v2: usize, weight: W) -> TreeEdge<W> {
+    TreeEdge::new(v1.min(v2),v1.max(v2),weight)
+}
@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use log::warn;
+use crate::error::{Error,Result};
+use crate::weight::Weight;
+
+
+// what to do when an edge joins two vertexes that already have an edge between them
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default)]
+pub enum ParallelEdgePolicy {
+    #[default]
+    KeepAll,
+    KeepMin,
+    KeepMax,
+    // replace them with a single edge weighing the total
+    Sum,
+    Reject,
+}
+
+impl ParallelEdgePolicy {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "keep-all" => Some(ParallelEdgePolicy::KeepAll),
+            "keep-min" | "min" => Some(ParallelEdgePolicy::KeepMin),
+            "keep-max" | "max" => Some(ParallelEdgePolicy::KeepMax),
+            "sum" => Some(ParallelEdgePolicy::Sum),
+            "reject" => Some(ParallelEdgePolicy::Reject),
+            _ => None,
+        }
+    }
+}
+
+// what to do with an edge from a vertex to itself
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default)]
+pub enum SelfLoopPolicy {
+    #[default]
+    Keep,
+    Drop,
+    Reject,
+}
+
+impl SelfLoopPolicy {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "keep" => Some(SelfLoopPolicy::Keep),
+            "drop" => Some(SelfLoopPolicy::Drop),
+            "reject" => Some(SelfLoopPolicy::Reject),
+            _ => None,
+        }
+    }
+}
+
+
+// how an edge passed to EdgeMerger::add should be stored
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum Merge<W> {
+    Add,
+    // the edge stored in slot should have its weight changed from old to new
+    Replace { slot: usize, old: W, new: W },
+    Skip,
+}
+
+// applies the parallel edge and self loop policies as edges are loaded, remembering
+// the weight kept for each pair of vertexes (and the slot the caller stored it in)
+pub struct EdgeMerger<W> {
+    parallel_edges: ParallelEdgePolicy,
+    self_loops: SelfLoopPolicy,
+    directed: bool,
+    kept: HashMap<(usize,usize),(W,usize)>,
+    pub merged: usize,
+    pub dropped_loops: usize,
+}
+
+impl<W: Weight> EdgeMerger<W> {
+
+    pub fn new(parallel_edges: ParallelEdgePolicy, self_loops: SelfLoopPolicy, directed: bool) -> Self {
+        EdgeMerger { parallel_edges, self_loops, directed, kept: HashMap::<(usize,usize),(W,usize)>::new(), merged: 0, dropped_loops: 0 }
+    }
+
+    // slot is where the caller will store the edge if it is added
+    pub fn add(&mut self, src: usize, dest: usize, weight: W, slot: usize) -> Result<Merge<W>> {
+        if src == dest {
+            match self.self_loops {
+                SelfLoopPolicy::Keep => (),
+                SelfLoopPolicy::Drop => {
+                    self.dropped_loops += 1;
+                    return Ok(Merge::Skip);
+                },
+                SelfLoopPolicy::Reject => return Err(Error::SelfLoop(src)),
+            }
+        }
+        if self.parallel_edges == ParallelEdgePolicy::KeepAll {
+            return Ok(Merge::Add);
+        }
+
+        let key = if self.directed { (src,dest) } else { (src.min(dest),src.max(dest)) };
+        let Some((old, kept_slot)) = self.kept.get(&key).copied() else {
+            self.kept.insert(key,(weight,slot));
+            return Ok(Merge::Add);
+        };
+
+        let new = match self.parallel_edges {
+            ParallelEdgePolicy::KeepMin if weight < old => weight,
+            ParallelEdgePolicy::KeepMax if weight > old => weight,
+            ParallelEdgePolicy::Sum => old + weight,
+            ParallelEdgePolicy::Reject => return Err(Error::ParallelEdge { src, dest }),
+            _ => old,
+        };
+        self.merged += 1;
+        if new == old {
+            return Ok(Merge::Skip);
+        }
+        self.kept.insert(key,(new,kept_slot));
+        Ok(Merge::Replace { slot: kept_slot, old, new })
+    }
+
+    pub fn log_counts(&self) {
+        if self.merged > 0 {
+            warn!("Merged {} parallel edges ({:?})",self.merged,self.parallel_edges);
+        }
+        if self.dropped_loops > 0 {
+            warn!("Dropped {} self loops",self.dropped_loops);
+        }
+    }
+
+}
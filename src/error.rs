@@ -0,0 +1,64 @@
+use std::fmt;
+use std::io;
+use crate::paths::NegativeCycle;
+
+
+#[derive(Debug)]
+pub enum Error {
+    // the input couldn't be opened or read
+    Io { path: String, source: io::Error },
+    // a line of the input couldn't be parsed, line and column start at 1
+    Parse { line: usize, column: usize, message: String },
+    // the counts in the header line don't match the data that followed
+    HeaderMismatch { what: String, expected: usize, found: usize },
+    // compressed input that this build can't decompress
+    UnsupportedCompression(String),
+    UnknownVertex(usize),
+    UnknownEdge { src: usize, dest: usize },
+    InvalidStartVertex(usize),
+    NegativeCycle(NegativeCycle),
+    // spanning trees are only defined for undirected graphs
+    DirectedGraph,
+    // rejected by the ParallelEdgePolicy / SelfLoopPolicy in the load options
+    ParallelEdge { src: usize, dest: usize },
+    SelfLoop(usize),
+}
+
+pub type Result<T> = std::result::Result<T,Error>;
+
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io { path, source } => write!(f,"couldn't read {}: {}",path,source),
+            Error::Parse { line, column, message } => write!(f,"line {} column {}: {}",line,column,message),
+            Error::HeaderMismatch { what, expected, found } => write!(f,"header declares {} {} but {} were read",expected,what,found),
+            Error::UnsupportedCompression(kind) => write!(f,"{} compressed input is not supported by this build",kind),
+            Error::UnknownVertex(v) => write!(f,"vertex {} is not in the graph",v),
+            Error::UnknownEdge { src, dest } => write!(f,"edge {} - {} is not in the graph",src,dest),
+            Error::InvalidStartVertex(v) => write!(f,"starting vertex {} is not in the graph",v),
+            Error::NegativeCycle(cycle) => {
+                let cycle_str : Vec<String> = cycle.vertices.iter().map(|v| v.to_string()).collect();
+                write!(f,"negative cycle {}",cycle_str.join(" -> "))
+            },
+            Error::DirectedGraph => write!(f,"spanning trees need an undirected graph"),
+            Error::ParallelEdge { src, dest } => write!(f,"parallel edge between {} and {}",src,dest),
+            Error::SelfLoop(v) => write!(f,"self loop on vertex {}",v),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<NegativeCycle> for Error {
+    fn from(cycle: NegativeCycle) -> Self {
+        Error::NegativeCycle(cycle)
+    }
+}
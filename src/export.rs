@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use crate::graph::Graph;
+use crate::spanning_tree::SpanningTree;
+use crate::error::{Error,Result};
+use crate::weight::Weight;
+
+
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum ExportFormat {
+    Dot,
+    GraphMl,
+    Json,
+}
+
+impl ExportFormat {
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "dot" | "gv" => Some(ExportFormat::Dot),
+            "graphml" => Some(ExportFormat::GraphMl),
+            "json" => Some(ExportFormat::Json),
+            _ => None,
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Option<Self> {
+        ExportFormat::from_name(path.extension()?.to_str()?)
+    }
+
+}
+
+
+// an edge to be written out, flagged if it is part of the spanning tree
+struct ExportEdge<W> {
+    src: usize,
+    dest: usize,
+    weight: W,
+    in_tree: bool,
+}
+
+// undirected graphs store every edge in both directions, so only write each one once
+fn export_edges<W: Weight>(graph: &Graph<W>, tree: Option<&SpanningTree<W>>) -> Result<Vec<ExportEdge<W>>> {
+    // weights aren't necessarily hashable, so keep the tree weights between each pair
+    let mut tree_edges = HashMap::<(usize,usize),Vec<W>>::new();
+    for e in tree.map(|t| t.edges.as_slice()).unwrap_or_default() {
+        tree_edges.entry((e.src.min(e.dest),e.src.max(e.dest))).or_default().push(e.weight);
+    }
+
+    let mut edges = Vec::<ExportEdge<W>>::new();
+    for src in graph.get_vertexes() {
+        for edge in graph.get_outgoing(src)? {
+            if !graph.is_directed() && edge.vertex < src {
+                continue;
+            }
+            let in_tree = tree_edges.get(&(src.min(edge.vertex),src.max(edge.vertex))).is_some_and(|w| w.contains(&edge.weight));
+            edges.push(ExportEdge { src, dest: edge.vertex, weight: edge.weight, in_tree });
+        }
+    }
+    Ok(edges)
+}
+
+fn write_dot<W: Weight>(out: &mut dyn Write, graph: &Graph<W>, edges: &[ExportEdge<W>]) -> io::Result<()> {
+    let (kind, connector) = if graph.is_directed() { ("digraph","->") } else { ("graph","--") };
+    writeln!(out,"{} G {{",kind)?;
+    for v in graph.get_vertexes() {
+        writeln!(out,"    {};",v)?;
+    }
+    for e in edges {
+        if e.in_tree {
+            writeln!(out,"    {} {} {} [label=\"{}\", color=red, penwidth=3];",e.src,connector,e.dest,e.weight)?;
+        }
+        else {
+            writeln!(out,"    {} {} {} [label=\"{}\"];",e.src,connector,e.dest,e.weight)?;
+        }
+    }
+    writeln!(out,"}}")
+}
+
+fn write_graphml<W: Weight>(out: &mut dyn Write, graph: &Graph<W>, edges: &[ExportEdge<W>]) -> io::Result<()> {
+    writeln!(out,"<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    writeln!(out,"<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">")?;
+    let weight_type = if W::INTEGRAL { "long" } else { "double" };
+    writeln!(out,"  <key id=\"weight\" for=\"edge\" attr.name=\"weight\" attr.type=\"{}\"/>",weight_type)?;
+    writeln!(out,"  <key id=\"tree\" for=\"edge\" attr.name=\"tree\" attr.type=\"boolean\">")?;
+    writeln!(out,"    <default>false</default>")?;
+    writeln!(out,"  </key>")?;
+    writeln!(out,"  <graph id=\"G\" edgedefault=\"{}\">",if graph.is_directed() { "directed" } else { "undirected" })?;
+    for v in graph.get_vertexes() {
+        writeln!(out,"    <node id=\"n{}\"/>",v)?;
+    }
+    for (i, e) in edges.iter().enumerate() {
+        writeln!(out,"    <edge id=\"e{}\" source=\"n{}\" target=\"n{}\">",i,e.src,e.dest)?;
+        writeln!(out,"      <data key=\"weight\">{}</data>",e.weight)?;
+        if e.in_tree {
+            writeln!(out,"      <data key=\"tree\">true</data>")?;
+        }
+        writeln!(out,"    </edge>")?;
+    }
+    writeln!(out,"  </graph>")?;
+    writeln!(out,"</graphml>")
+}
+
+// JSON has no infinity, so a weight that isn't finite is written as null
+fn json_weight<W: Weight>(weight: W) -> String {
+    if weight.is_finite() { weight.to_string() } else { "null".to_string() }
+}
+
+fn write_json<W: Weight>(out: &mut dyn Write, graph: &Graph<W>, edges: &[ExportEdge<W>], tree: Option<&SpanningTree<W>>) -> io::Result<()> {
+    writeln!(out,"{{")?;
+    writeln!(out,"  \"directed\": {},",graph.is_directed())?;
+    let nodes : Vec<String> = graph.get_vertexes().iter().map(|v| format!("    {{\"id\": {}}}",v)).collect();
+    writeln!(out,"  \"nodes\": [\n{}\n  ],",nodes.join(",\n"))?;
+    let edge_list : Vec<String> = edges.iter().map(|e| {
+        format!("    {{\"source\": {}, \"target\": {}, \"weight\": {}, \"tree\": {}}}",e.src,e.dest,json_weight(e.weight),e.in_tree)
+    }).collect();
+    match tree {
+        Some(tree) => {
+            writeln!(out,"  \"edges\": [\n{}\n  ],",edge_list.join(",\n"))?;
+            writeln!(out,"  \"tree_weight\": {},",json_weight(tree.total_weight()))?;
+            writeln!(out,"  \"tree_components\": {}",tree.component_count())?;
+        },
+        None => writeln!(out,"  \"edges\": [\n{}\n  ]",edge_list.join(",\n"))?,
+    }
+    writeln!(out,"}}")
+}
+
+// writes the graph, highlighting the edges of tree (if given) and labelling each edge
+// with its weight
+pub fn export_graph<W: Weight>(out: &mut dyn Write, graph: &Graph<W>, tree: Option<&SpanningTree<W>>, format: ExportFormat) -> Result<()> {
+    let edges = export_edges(graph,tree)?;
+    match format {
+        ExportFormat::Dot => write_dot(out,graph,&edges),
+        ExportFormat::GraphMl => write_graphml(out,graph,&edges),
+        ExportFormat::Json => write_json(out,graph,&edges,tree),
+    }.map_err(|e| Error::Io { path: "export output".to_string(), source: e })
+}
+
+pub fn export_to_file<W: Weight>(filename: &str, graph: &Graph<W>, tree: Option<&SpanningTree<W>>, format: ExportFormat) -> Result<()> {
+    let file = File::create(filename).map_err(|e| Error::Io { path: filename.to_string(), source: e })?;
+    let mut out = BufWriter::new(file);
+    export_graph(&mut out,graph,tree,format)?;
+    out.flush().map_err(|e| Error::Io { path: filename.to_string(), source: e })
+}
@@ -0,0 +1,285 @@
+use std::io::{BufRead, Read};
+use std::thread;
+use std::time::Instant;
+use log::info;
+use crate::graph::Graph;
+use crate::error::{Error,Result};
+use crate::input::open_input;
+use crate::loader::LoadOptions;
+use crate::readers::{GraphReader,GraphBuilder,EdgeSink,EdgeListReader};
+use crate::csr::{CsrGraph,CsrBuilder};
+use crate::weight::Weight;
+
+
+// what one chunk of the file contained, in file order
+struct Chunk<W> {
+    // isolated vertexes and edges, in the order they appeared
+    items: Vec<Item<W>>,
+    lines: usize,
+}
+
+// the (vertex count, edge count) header and the chunks of the body, in file order
+type ParsedChunks<W> = ((usize,usize),Vec<Chunk<W>>);
+
+enum Item<W> {
+    Vertex(usize),
+    Edge(usize,usize,W),
+}
+
+// a parse error with the line relative to the start of its chunk
+struct ChunkError {
+    line: usize,
+    column: usize,
+    message: String,
+}
+
+
+// returns the next whitespace separated field in line starting from pos, along with
+// the offset it starts at
+fn next_field<'a>(line: &'a [u8], pos: &mut usize) -> Option<(usize,&'a [u8])> {
+    while *pos < line.len() && line[*pos].is_ascii_whitespace() {
+        *pos += 1;
+    }
+    if *pos == line.len() {
+        return None;
+    }
+    let start = *pos;
+    while *pos < line.len() && !line[*pos].is_ascii_whitespace() {
+        *pos += 1;
+    }
+    Some((start,&line[start..*pos]))
+}
+
+// accepts what usize::from_str does, an optional + and then digits
+fn parse_unsigned(field: &[u8]) -> Option<usize> {
+    let field = field.strip_prefix(b"+").unwrap_or(field);
+    if field.is_empty() {
+        return None;
+    }
+    let mut value : usize = 0;
+    for b in field {
+        if !b.is_ascii_digit() {
+            return None;
+        }
+        value = value.checked_mul(10)?.checked_add((b - b'0') as usize)?;
+    }
+    Some(value)
+}
+
+fn parse_weight<W: Weight>(field: &[u8]) -> Option<W> {
+    std::str::from_utf8(field).ok().and_then(W::parse)
+}
+
+// parses "src dest weight" (or lone vertex) lines, the same rules as EdgeListReader
+fn parse_chunk<W: Weight>(data: &[u8]) -> std::result::Result<Chunk<W>,ChunkError> {
+    let mut chunk = Chunk { items: Vec::<Item<W>>::new(), lines: 0 };
+    if data.is_empty() {
+        return Ok(chunk);
+    }
+
+    // a trailing newline doesn't start another line
+    let data = data.strip_suffix(b"\n").unwrap_or(data);
+    for (line_num, line) in data.split(|b| *b == b'\n').enumerate() {
+        chunk.lines += 1;
+
+        let mut pos = 0;
+        let error = |column: usize, message: String| ChunkError { line: line_num, column: column + 1, message };
+        let text = || String::from_utf8_lossy(line).trim().to_string();
+
+        let Some((src_col, src)) = next_field(line,&mut pos) else { continue };
+        let Some((dest_col, dest)) = next_field(line,&mut pos) else {
+            // an isolated vertex
+            match parse_unsigned(src) {
+                Some(vertex) => { chunk.items.push(Item::Vertex(vertex)); continue; },
+                None => return Err(error(src_col,format!("expected \"src dest weight\" but found \"{}\"",text()))),
+            }
+        };
+        let Some((weight_col, weight)) = next_field(line,&mut pos) else {
+            return Err(error(src_col,format!("expected \"src dest weight\" but found \"{}\"",text())));
+        };
+
+        let field_error = |column: usize, name: &str, field: &[u8]| error(column,format!("invalid {} \"{}\"",name,String::from_utf8_lossy(field)));
+        let src = parse_unsigned(src).ok_or_else(|| field_error(src_col,"source vertex",src))?;
+        let dest = parse_unsigned(dest).ok_or_else(|| field_error(dest_col,"destination vertex",dest))?;
+        let weight = parse_weight(weight).ok_or_else(|| field_error(weight_col,&format!("{} weight",W::NAME),weight))?;
+        chunk.items.push(Item::Edge(src,dest,weight));
+    }
+    Ok(chunk)
+}
+
+// split data into roughly equal pieces, each ending just after a newline
+fn split_chunks(data: &[u8], count: usize) -> Vec<&[u8]> {
+    let target = data.len().div_ceil(count.max(1)).max(1);
+    let mut chunks = Vec::<&[u8]>::new();
+    let mut start = 0;
+    while start < data.len() {
+        let mut end = (start + target).min(data.len());
+        while end < data.len() && data[end - 1] != b'\n' {
+            end += 1;
+        }
+        chunks.push(&data[start..end]);
+        start = end;
+    }
+    chunks
+}
+
+
+// maps filename into memory, unless it is stdin or compressed
+#[cfg(feature = "mmap")]
+fn map_file(filename: &str) -> Result<Option<memmap2::Mmap>> {
+    use crate::input::Compression;
+
+    if filename == "-" || Compression::from_path(std::path::Path::new(filename)) != Compression::None {
+        return Ok(None);
+    }
+    let io_error = |e| Error::Io { path: filename.to_string(), source: e };
+    let file = std::fs::File::open(filename).map_err(io_error)?;
+    // the file must not be modified while it is mapped
+    let data = unsafe { memmap2::Mmap::map(&file) }.map_err(io_error)?;
+    if Compression::from_magic(&data) != Compression::None {
+        return Ok(None);
+    }
+    info!("Memory mapped {}",filename);
+    Ok(Some(data))
+}
+
+
+// byte level parser for the "src dest weight" edge list format, avoiding the per line
+// String allocation and regex matching of EdgeListReader. The body of the file can be
+// split into chunks that are parsed on separate threads.
+#[derive(Debug,Clone)]
+pub struct FastEdgeListReader {
+    pub threads: usize,
+    // memory map plain files instead of reading them into memory
+    pub mmap: bool,
+}
+
+impl FastEdgeListReader {
+
+    pub fn new(threads: usize, mmap: bool) -> Self {
+        FastEdgeListReader { threads: threads.max(1), mmap }
+    }
+
+    // splits the body into chunks and parses them, returning the header and each chunk's
+    // contents in file order
+    fn parse_chunks<W: Weight>(&self, data: &[u8]) -> Result<ParsedChunks<W>> {
+        // the first line is the header
+        let header_end = data.iter().position(|b| *b == b'\n').map_or(data.len(), |p| p + 1);
+        let header_line = String::from_utf8_lossy(&data[..header_end]);
+        let header = EdgeListReader::parse_header(header_line.trim_end_matches(['\n','\r']))?;
+        let body = &data[header_end..];
+
+        let chunks = split_chunks(body,self.threads);
+        let results : Vec<std::result::Result<Chunk<W>,ChunkError>> = if chunks.len() > 1 {
+            thread::scope(|scope| {
+                let workers : Vec<_> = chunks.iter().map(|c| scope.spawn(move || parse_chunk(c))).collect();
+                workers.into_iter().map(|w| w.join().expect("parser thread panicked")).collect()
+            })
+        }
+        else {
+            chunks.iter().map(|c| parse_chunk(c)).collect()
+        };
+
+        // chunk errors are relative to the chunk, so count lines to report where they are
+        let mut parsed = Vec::<Chunk<W>>::with_capacity(results.len());
+        let mut first_line = 2;
+        for result in results {
+            let chunk = result.map_err(|e| Error::Parse { line: first_line + e.line, column: e.column, message: e.message })?;
+            first_line += chunk.lines;
+            parsed.push(chunk);
+        }
+        Ok((header,parsed))
+    }
+
+    fn log_stats(&self, bytes: usize, edges: usize, started: Instant, parsed: Instant) {
+        let seconds = started.elapsed().as_secs_f64();
+        info!("Parsed {} bytes ({} edges) in {:.3}s with {} thread(s): {:.1} MB/s, {:.0} edges/s ({:.3}s parsing, {:.3}s building the graph)",
+            bytes,edges,seconds,self.threads,
+            bytes as f64 / 1_000_000.0 / seconds,edges as f64 / seconds,
+            (parsed - started).as_secs_f64(),parsed.elapsed().as_secs_f64());
+    }
+
+    pub fn parse<W: Weight>(&self, data: &[u8], options: &LoadOptions) -> Result<Graph<W>> {
+        let started = Instant::now();
+        let (header, chunks) = self.parse_chunks(data)?;
+        let parsed = Instant::now();
+
+        let mut builder = GraphBuilder::new(options);
+        add_items(chunks,&mut builder)?;
+        let edges = builder.edge_count();
+        let graph = builder.finish(Some(header))?;
+        self.log_stats(data.len(),edges,started,parsed);
+        Ok(graph)
+    }
+
+    // same as parse, but builds a CsrGraph without going through a Graph
+    pub fn parse_csr<W: Weight>(&self, data: &[u8], options: &LoadOptions) -> Result<CsrGraph<W>> {
+        let started = Instant::now();
+        let (header, chunks) = self.parse_chunks(data)?;
+        let parsed = Instant::now();
+
+        let mut builder = CsrBuilder::new(options);
+        add_items(chunks,&mut builder)?;
+        let edges = builder.edge_count();
+        let graph = builder.finish(Some(header))?;
+        self.log_stats(data.len(),edges,started,parsed);
+        Ok(graph)
+    }
+
+    // memory maps plain files when enabled, anything else (stdin, compressed) is read into
+    // memory, and hands the contents to parse
+    fn with_data<T>(&self, filename: &str, parse: impl FnOnce(&[u8]) -> Result<T>) -> Result<T> {
+        #[cfg(feature = "mmap")]
+        if self.mmap {
+            if let Some(data) = map_file(filename)? {
+                return parse(&data);
+            }
+        }
+
+        let mut data = Vec::<u8>::new();
+        open_input(filename)?.read_to_end(&mut data).map_err(|e| Error::Io { path: filename.to_string(), source: e })?;
+        parse(&data)
+    }
+
+    pub fn load<W: Weight>(&self, filename: &str, options: &LoadOptions) -> Result<Graph<W>> {
+        self.with_data(filename,|data| self.parse(data,options))
+    }
+
+    pub fn load_csr<W: Weight>(&self, filename: &str, options: &LoadOptions) -> Result<CsrGraph<W>> {
+        self.with_data(filename,|data| self.parse_csr(data,options))
+    }
+
+}
+
+// adds everything to sink in file order, so the result is the same however many
+// threads were used
+fn add_items<W: Weight>(chunks: Vec<Chunk<W>>, sink: &mut dyn EdgeSink<W>) -> Result<()> {
+    for item in chunks.into_iter().flat_map(|c| c.items) {
+        match item {
+            Item::Vertex(v) => sink.add_vertex(v),
+            Item::Edge(src,dest,weight) => sink.add_edge(src,dest,weight)?,
+        }
+    }
+    Ok(())
+}
+
+fn read_all(reader: &mut dyn BufRead) -> Result<Vec<u8>> {
+    let mut data = Vec::<u8>::new();
+    reader.read_to_end(&mut data).map_err(|e| Error::Io { path: "input".to_string(), source: e })?;
+    Ok(data)
+}
+
+impl<W: Weight> GraphReader<W> for FastEdgeListReader {
+
+    fn read_into(&self, reader: &mut dyn BufRead, _options: &LoadOptions, sink: &mut dyn EdgeSink<W>) -> Result<Option<(usize,usize)>> {
+        let (header, chunks) = self.parse_chunks(&read_all(reader)?)?;
+        add_items(chunks,sink)?;
+        Ok(Some(header))
+    }
+
+    // parse logs its throughput, so it is used directly rather than through read_into
+    fn read(&self, reader: &mut dyn BufRead, options: &LoadOptions) -> Result<Graph<W>> {
+        self.parse(&read_all(reader)?,options)
+    }
+
+}
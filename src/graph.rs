@@ -1,37 +1,61 @@
+use std::cmp::Ordering;
 use std::collections::{HashMap,BTreeMap};
+use crate::error::{self,Error};
+use crate::weight::Weight;
 
 
 static mut MAX_OUT_LEVEL : u32= 0;
 static mut MAX_IN_LEVEL : u32 = 0;
 
 #[derive(Debug, Clone)]
-pub struct Vertex {
+pub struct Vertex<W = i32> {
 	pub vertex_id: usize,
     // list of unique incoming edges along with a count of how many duplicates there are
-	incoming: BTreeMap<Edge,usize>,
+	incoming: BTreeMap<Edge<W>,usize>,
 	incoming_cnt: usize,
     // list of unique outgoing edges along with a count of how many duplicates there are
-	outgoing: BTreeMap<Edge,usize>,
+	outgoing: BTreeMap<Edge<W>,usize>,
 	outgoing_cnt: usize,
 }
 
-#[derive(Debug,Clone,Ord,PartialOrd,Eq,PartialEq)]
-pub struct Edge {
+#[derive(Debug,Clone,Copy)]
+pub struct Edge<W = i32> {
     pub vertex: usize,
-    pub weight: i32
+    pub weight: W
 }
 
-impl Edge {
-    pub fn new(v : usize, w: i32 ) -> Self {
+impl<W: Weight> Edge<W> {
+    pub fn new(v : usize, w: W ) -> Self {
         Edge { vertex: v, weight: w }
     }
 }
 
-impl Vertex {
+// weights are only PartialOrd (floats), so edges are ordered using the weight's total order
+impl<W: Weight> Ord for Edge<W> {
+	fn cmp(&self, other: &Self) -> Ordering {
+		self.vertex.cmp(&other.vertex).then_with(|| self.weight.total_cmp(&other.weight))
+	}
+}
+
+impl<W: Weight> PartialOrd for Edge<W> {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl<W: Weight> PartialEq for Edge<W> {
+	fn eq(&self, other: &Self) -> bool {
+		self.cmp(other) == Ordering::Equal
+	}
+}
+
+impl<W: Weight> Eq for Edge<W> {}
 
-	pub fn new(id : &usize) -> Vertex {
-		let incoming = BTreeMap::<Edge,usize>::new();
-		let outgoing = BTreeMap::<Edge,usize>::new();
+impl<W: Weight> Vertex<W> {
+
+	pub fn new(id : &usize) -> Vertex<W> {
+		let incoming = BTreeMap::<Edge<W>,usize>::new();
+		let outgoing = BTreeMap::<Edge<W>,usize>::new();
 		Vertex {vertex_id: id.clone(), 
 				incoming: incoming, 
 				outgoing: outgoing,
@@ -40,14 +64,14 @@ impl Vertex {
 				}
 	}
 	
-	pub fn add_outgoing(&mut self, vertex_id: usize, weight: i32) {
+	pub fn add_outgoing(&mut self, vertex_id: usize, weight: W) {
         let edge = Edge {vertex: vertex_id, weight: weight };
 		let counter = self.outgoing.entry(edge).or_insert(0);
 		*counter += 1;
 		self.outgoing_cnt += 1;
 	}
 
-	pub fn del_outgoing (&mut self, vertex_id: usize, weight: i32) ->  Result <(), String> {
+	pub fn del_outgoing (&mut self, vertex_id: usize, weight: W) ->  Result <(), String> {
 
         let edge = Edge {vertex: vertex_id, weight: weight };
 
@@ -65,14 +89,14 @@ impl Vertex {
 		}
 	}
 
-	pub fn add_incoming(&mut self, vertex_id: usize, weight: i32) {
+	pub fn add_incoming(&mut self, vertex_id: usize, weight: W) {
         let edge = Edge {vertex: vertex_id, weight: weight };
 		let counter = self.incoming.entry(edge).or_insert(0);
 		*counter += 1;
 		self.incoming_cnt += 1;
 	}
 
-	pub fn del_incoming (&mut self, vertex_id: usize, weight: i32) -> Result<(),String> {
+	pub fn del_incoming (&mut self, vertex_id: usize, weight: W) -> Result<(),String> {
 	
         let edge = Edge {vertex: vertex_id, weight: weight };
 		match self.incoming.get_mut(&edge) {
@@ -94,9 +118,11 @@ impl Vertex {
 
 
 #[derive(Debug,Clone)]
-pub struct Graph {
-	pub vertex_map:  BTreeMap::<usize, Vertex>,
+pub struct Graph<W = i32> {
+	pub vertex_map:  BTreeMap::<usize, Vertex<W>>,
 	edge_count:  usize,
+	// undirected edges are counted once but appear in both vertexes' outgoing and incoming lists
+	directed:  bool,
 	explored:  HashMap::<usize,bool>,
 	pub finished_order:  Vec::<usize>,
 	pub start_search:  HashMap::<usize,Vec::<usize>>,
@@ -104,12 +130,19 @@ pub struct Graph {
 }
 
 
-impl Graph {
-	pub fn new() -> Graph {
-		let v_map = BTreeMap::<usize, Vertex>::new();
+impl<W: Weight> Default for Graph<W> {
+	fn default() -> Self {
+		Graph::new()
+	}
+}
+
+impl<W: Weight> Graph<W> {
+	pub fn new() -> Graph<W> {
+		let v_map = BTreeMap::<usize, Vertex<W>>::new();
 		Graph {
 				vertex_map: v_map,
 				edge_count: 0,
+				directed: true,
 				explored:  HashMap::<usize,bool>::new(),
 				finished_order:  Vec::<usize>::new(),
 				start_search : HashMap::<usize,Vec::<usize>>::new(),
@@ -118,15 +151,30 @@ impl Graph {
 	}
 
 
-	pub fn get_outgoing(&self, vertex: usize) -> Vec<Edge>{
-		let v = self.vertex_map.get(&vertex).unwrap();
-		v.outgoing.keys().cloned().collect()
+	pub fn new_undirected() -> Graph<W> {
+		Graph { directed: false, ..Graph::new() }
+	}
+
+	pub fn is_directed(&self) -> bool {
+		self.directed
+	}
+
+
+	pub fn get_outgoing(&self, vertex: usize) -> error::Result<Vec<Edge<W>>> {
+		let v = self.vertex_map.get(&vertex).ok_or(Error::UnknownVertex(vertex))?;
+		Ok(v.outgoing.keys().cloned().collect())
 		
 	}
 
-	pub fn get_incoming(&self,vertex: usize) -> Vec<Edge> {
-		let v = self.vertex_map.get(&vertex).unwrap();
-		v.incoming.keys().cloned().collect()
+	// each distinct outgoing edge with the number of copies of it
+	pub fn get_outgoing_counts(&self, vertex: usize) -> error::Result<Vec<(Edge<W>,usize)>> {
+		let v = self.vertex_map.get(&vertex).ok_or(Error::UnknownVertex(vertex))?;
+		Ok(v.outgoing.iter().map(|(e, count)| (*e,*count)).collect())
+	}
+
+	pub fn get_incoming(&self,vertex: usize) -> error::Result<Vec<Edge<W>>> {
+		let v = self.vertex_map.get(&vertex).ok_or(Error::UnknownVertex(vertex))?;
+		Ok(v.incoming.keys().cloned().collect())
 		
 	}
 
@@ -136,6 +184,14 @@ impl Graph {
 			
 	}
 
+	pub fn edge_count(&self) -> usize {
+		self.edge_count
+	}
+
+	pub fn has_negative_edges(&self) -> bool {
+		self.vertex_map.values().any(|v| v.outgoing.keys().any(|e| e.weight < W::ZERO))
+	}
+
 	pub fn print_vertexes(&self) {
 		for (key, value) in &self.vertex_map {
 			let out_list : String = value.outgoing.iter().map(|(x, y)| if y > &1 {format!("{:?}({}) ; ",x,y) } else { format!("{:?} ;",x)}).collect();
@@ -196,7 +252,7 @@ impl Graph {
 			self.add_search_entry(start_vertex,cur_len);
 
 			
-			let next_v : Vertex;
+			let next_v : Vertex<W>;
 
 			if let Some(vertex) = self.vertex_map.get(&vertex_id) {
 
@@ -239,7 +295,7 @@ impl Graph {
 			let cur_len = group_list.len();
 			self.add_search_entry(start_vertex,cur_len);
 
-			let next_v : Vertex;
+			let next_v : Vertex<W>;
 
 			if let Some(vertex) = self.vertex_map.get(&vertex_id) {
 
@@ -308,7 +364,7 @@ impl Graph {
 		}
 	}
 
-	pub fn add_edge(&mut self, v1: usize, v2: usize, weight: i32) -> Option<usize> {
+	pub fn add_edge(&mut self, v1: usize, v2: usize, weight: W) -> Option<usize> {
 
 		//create the vertexes, if the don't exist
 		self.create_vertex(&v1);
@@ -324,15 +380,26 @@ impl Graph {
 		let vert2 = v_map.get_mut(&v2).unwrap(); 
 		vert2.add_incoming(v1,weight);
 
+		// an undirected edge can be followed from either end (a self loop is only stored once)
+		if !self.directed && v1 != v2 {
+			vert2.add_outgoing(v1,weight);
+			v_map.get_mut(&v1).unwrap().add_incoming(v2,weight);
+		}
+
 		self.edge_count += 1;
 		Some(new_cnt)
 
 	}
 
-	pub fn delete_edge(&mut self,v1 : usize, v2 : usize, weight: i32) -> Result<(),String>  {
+	pub fn delete_edge(&mut self,v1 : usize, v2 : usize, weight: W) -> error::Result<()>  {
 	
-		self.vertex_map.get_mut(&v1).unwrap().del_outgoing(v2,weight)?	;
-		self.vertex_map.get_mut(&v2).unwrap().del_incoming(v1,weight)?;
+		let unknown = |_| Error::UnknownEdge { src: v1, dest: v2 };
+		self.vertex_map.get_mut(&v1).ok_or(Error::UnknownVertex(v1))?.del_outgoing(v2,weight).map_err(unknown)?	;
+		self.vertex_map.get_mut(&v2).ok_or(Error::UnknownVertex(v2))?.del_incoming(v1,weight).map_err(unknown)?;
+		if !self.directed && v1 != v2 {
+			self.vertex_map.get_mut(&v2).unwrap().del_outgoing(v1,weight).map_err(unknown)?;
+			self.vertex_map.get_mut(&v1).unwrap().del_incoming(v2,weight).map_err(unknown)?;
+		}
 		self.edge_count -= 1;
 		Ok(())
 
@@ -340,4 +407,49 @@ impl Graph {
 
 }
 
-            
+
+// read only access to a graph's vertexes and outgoing edges, so the algorithms can run
+// on any storage (Graph, CsrGraph or an adjacency store of your own) without copying
+pub trait GraphView {
+	type Weight: Weight;
+
+	fn vertex_count(&self) -> usize;
+	// undirected graphs list each edge from both ends
+	fn is_directed(&self) -> bool;
+	// every vertex id, in increasing order
+	fn vertices(&self) -> impl Iterator<Item=usize> + '_;
+	fn contains_vertex(&self, vertex: usize) -> bool;
+	// (target vertex, weight) of each edge leaving vertex. Every copy of a parallel edge
+	// is its own item, so all storages list the same edges for the same graph.
+	fn neighbors(&self, vertex: usize) -> error::Result<impl Iterator<Item=(usize,Self::Weight)> + '_>;
+
+	fn has_negative_edges(&self) -> bool {
+		self.vertices().any(|v| self.neighbors(v).is_ok_and(|mut edges| edges.any(|(_, w)| w < Self::Weight::ZERO)))
+	}
+}
+
+impl<W: Weight> GraphView for Graph<W> {
+	type Weight = W;
+
+	fn vertex_count(&self) -> usize {
+		self.vertex_map.len()
+	}
+
+	fn is_directed(&self) -> bool {
+		self.directed
+	}
+
+	fn vertices(&self) -> impl Iterator<Item=usize> + '_ {
+		self.vertex_map.keys().copied()
+	}
+
+	fn contains_vertex(&self, vertex: usize) -> bool {
+		self.vertex_map.contains_key(&vertex)
+	}
+
+	fn neighbors(&self, vertex: usize) -> error::Result<impl Iterator<Item=(usize,W)> + '_> {
+		let v = self.vertex_map.get(&vertex).ok_or(Error::UnknownVertex(vertex))?;
+		Ok(v.outgoing.iter().flat_map(|(e, count)| std::iter::repeat_n((e.vertex,e.weight),*count)))
+	}
+
+}
@@ -0,0 +1,95 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path,PathBuf};
+use log::info;
+use crate::error::{Error,Result};
+
+
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+    Xz,
+}
+
+impl Compression {
+
+    // identify compressed data from its first few bytes
+    pub fn from_magic(bytes: &[u8]) -> Self {
+        if bytes.starts_with(&[0x1f,0x8b]) {
+            Compression::Gzip
+        }
+        else if bytes.starts_with(&[0x28,0xb5,0x2f,0xfd]) {
+            Compression::Zstd
+        }
+        else if bytes.starts_with(&[0xfd,b'7',b'z',b'X',b'Z',0x00]) {
+            Compression::Xz
+        }
+        else {
+            Compression::None
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("gz") => Compression::Gzip,
+            Some("zst") => Compression::Zstd,
+            Some("xz") => Compression::Xz,
+            _ => Compression::None,
+        }
+    }
+
+}
+
+
+// the path without any compression extension, so "edges.mtx.gz" is read as "edges.mtx"
+pub fn uncompressed_path(path: &Path) -> PathBuf {
+    match Compression::from_path(path) {
+        Compression::None => path.to_path_buf(),
+        _ => path.with_extension(""),
+    }
+}
+
+fn io_error(name: &str, e: io::Error) -> Error {
+    Error::Io { path: name.to_string(), source: e }
+}
+
+// wraps the reader in a decompressor if the data starts with a known magic number,
+// falling back to the file extension if the data is too short to tell
+fn decompress(name: &str, mut reader: Box<dyn BufRead>, by_extension: Compression) -> Result<Box<dyn BufRead>> {
+    let magic = Compression::from_magic(reader.fill_buf().map_err(|e| io_error(name,e))?);
+    let compression = if magic != Compression::None { magic } else { by_extension };
+    if compression != Compression::None {
+        info!("Decompressing {} as {:?}",name,compression);
+    }
+
+    match compression {
+        Compression::None => Ok(reader),
+        #[cfg(feature = "gzip")]
+        Compression::Gzip => Ok(Box::new(BufReader::new(flate2::bufread::MultiGzDecoder::new(reader)))),
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => {
+            let decoder = zstd::stream::read::Decoder::with_buffer(reader).map_err(|e| io_error(name,e))?;
+            Ok(Box::new(BufReader::new(decoder)))
+        },
+        #[cfg(feature = "xz")]
+        Compression::Xz => Ok(Box::new(BufReader::new(xz2::bufread::XzDecoder::new_multi_decoder(reader)))),
+        #[allow(unreachable_patterns)]
+        other => Err(Error::UnsupportedCompression(format!("{:?}",other))),
+    }
+}
+
+// opens filename for reading, "-" reads from stdin, and compressed data is
+// transparently decompressed
+pub fn open_input(filename: &str) -> Result<Box<dyn BufRead>> {
+    if filename == "-" {
+        info!("Reading from stdin");
+        let stdin : Box<dyn Read> = Box::new(io::stdin());
+        return decompress("stdin",Box::new(BufReader::new(stdin)),Compression::None);
+    }
+
+    let path = Path::new(filename);
+    let file = File::open(path).map_err(|e| io_error(filename,e))?;
+    decompress(filename,Box::new(BufReader::new(file)),Compression::from_path(path))
+}
@@ -0,0 +1,98 @@
+use std::collections::HashSet;
+use crate::graph::GraphView;
+use crate::weight::Weight;
+use crate::kruskal::Kruskal;
+use crate::spanning_tree::{SpanningTree,TreeEdge,TreeKind};
+use crate::error::{Error,Result};
+use log::{info,debug};
+
+
+// the spanning trees that use every edge in include and none in exclude (indexes into
+// the ordered edge list), along with the best of them
+struct Partition<W> {
+    include: Vec<usize>,
+    exclude: Vec<usize>,
+    tree: Vec<usize>,
+    weight: W,
+}
+
+
+// enumerates distinct spanning trees in order of total weight using Lawler's method.
+// Each tree taken splits what is left of its partition into smaller partitions, and a
+// constrained Kruskal finds the best tree in each, so the next tree is always the best
+// of the open partitions.
+pub struct KBestTrees {
+    pub kind : TreeKind,
+}
+
+impl Default for KBestTrees {
+    fn default() -> Self {
+        KBestTrees::new()
+    }
+}
+
+impl KBestTrees {
+
+    pub fn new() -> Self {
+        KBestTrees {
+            kind : TreeKind::Minimum,
+        }
+    }
+
+    pub fn maximum() -> Self {
+        KBestTrees {
+            kind : TreeKind::Maximum,
+        }
+    }
+
+    // the k best spanning trees (or forests), best first. Fewer are returned if the
+    // graph doesn't have k distinct trees.
+    pub fn k_best<G: GraphView>(&self, graph: &G, k: usize) -> Result<Vec<SpanningTree<G::Weight>>> {
+        info!("Starting {} best {:?} spanning trees",k,self.kind);
+
+        if graph.is_directed() {
+            return Err(Error::DirectedGraph);
+        }
+
+        let vertexes : Vec<usize> = graph.vertices().collect();
+        let edges = Kruskal::ordered_edges(graph,self.kind)?;
+        let total = |tree: &[usize]| tree.iter().fold(G::Weight::ZERO,|total, i| total + edges[*i].0);
+
+        let mut trees = Vec::<SpanningTree<G::Weight>>::new();
+        // the unconstrained tree can't fail, and every other tree (or forest) of the
+        // graph has the same number of edges
+        let best = Kruskal::constrained_tree(&vertexes,&edges,&[],&[]).unwrap();
+        let size = best.len();
+        let mut open = vec!(Partition { include: Vec::<usize>::new(), exclude: Vec::<usize>::new(), weight: total(&best), tree: best });
+
+        while trees.len() < k {
+            // k is expected to be small, so a scan for the best open partition will do
+            let Some(next) = (0..open.len()).min_by(|a, b| self.kind.compare(&open[*a].weight,&open[*b].weight)) else { break };
+            let partition = open.swap_remove(next);
+
+            // for each edge of the tree that wasn't forced in, the trees that keep the
+            // edges before it but not it
+            let included : HashSet<usize> = partition.include.iter().copied().collect();
+            let mut include = partition.include.clone();
+            for i in partition.tree.iter().filter(|i| !included.contains(i)) {
+                let mut exclude = partition.exclude.clone();
+                exclude.push(*i);
+                if let Some(tree) = Kruskal::constrained_tree(&vertexes,&edges,&include,&exclude) {
+                    if tree.len() == size {
+                        open.push(Partition { include: include.clone(), exclude, weight: total(&tree), tree });
+                    }
+                }
+                include.push(*i);
+            }
+
+            let tree_edges : Vec<TreeEdge<G::Weight>> = partition.tree.iter().map(|i| {
+                let (weight,v1,v2) = edges[*i];
+                TreeEdge::new(v1,v2,weight)
+            }).collect();
+            debug!("Spanning tree #{} weighs {}, {} partitions open",trees.len() + 1,partition.weight,open.len());
+            trees.push(SpanningTree::from_edges(&vertexes,&tree_edges));
+        }
+        Ok(trees)
+    }
+
+}
@@ -0,0 +1,122 @@
+use crate::graph::GraphView;
+use crate::weight::Weight;
+use crate::spanning_tree::{SpanningTree,TreeEdge,TreeKind};
+use crate::union_find::UnionFind;
+use crate::error::{Error,Result};
+use log::info;
+
+
+pub struct Kruskal {
+    pub kind : TreeKind,
+}
+
+
+impl Default for Kruskal {
+    fn default() -> Self {
+        Kruskal::new()
+    }
+}
+
+impl Kruskal {
+
+    pub fn new() -> Self {
+        Kruskal {
+            kind : TreeKind::Minimum,
+        }
+    }
+
+    pub fn maximum() -> Self {
+        Kruskal { kind : TreeKind::Maximum }
+    }
+
+    // collect every edge once as (weight, low vertex, high vertex) sorted by weight,
+    // since undirected edges are stored in both directions in the graph
+    pub fn sorted_edges<G: GraphView>(graph: &G) -> Result<Vec<(G::Weight,usize,usize)>> {
+        let mut edges = Vec::<(G::Weight,usize,usize)>::new();
+        for v in graph.vertices() {
+            for (dest, weight) in graph.neighbors(v)? {
+                // self loops can never be part of a tree
+                if dest != v {
+                    edges.push((weight,v.min(dest),v.max(dest)));
+                }
+            }
+        }
+        edges.sort_by(|a, b| TreeKind::Minimum.compare_edges(a,b));
+        edges.dedup();
+        Ok(edges)
+    }
+
+    // sorted_edges in the order a tree of this kind prefers them
+    pub fn ordered_edges<G: GraphView>(graph: &G, kind: TreeKind) -> Result<Vec<(G::Weight,usize,usize)>> {
+        let mut edges = Kruskal::sorted_edges(graph)?;
+        if kind == TreeKind::Maximum {
+            edges.sort_by(|a, b| kind.compare_edges(a,b));
+        }
+        Ok(edges)
+    }
+
+    // Kruskal over edges that are already in order, where every edge in include must be
+    // in the tree and none in exclude may be (both are indexes into edges). Returns the
+    // indexes of the tree edges, or None if the included edges form a cycle.
+    pub fn constrained_tree<W: Weight>(vertexes: &[usize], edges: &[(W,usize,usize)], include: &[usize], exclude: &[usize]) -> Option<Vec<usize>> {
+        let mut sets = UnionFind::new();
+        for v in vertexes {
+            sets.make_set(*v);
+        }
+        let mut usable = vec![true; edges.len()];
+        for i in exclude {
+            usable[*i] = false;
+        }
+
+        let mut tree = Vec::<usize>::new();
+        for i in include {
+            let (_,v1,v2) = edges[*i];
+            if !sets.union(v1,v2) {
+                return None;
+            }
+            usable[*i] = false;
+            tree.push(*i);
+        }
+        for (i, (_,v1,v2)) in edges.iter().enumerate() {
+            if sets.set_count() == 1 {
+                break;
+            }
+            if usable[i] && sets.union(*v1,*v2) {
+                tree.push(i);
+            }
+        }
+        Some(tree)
+    }
+
+    // builds a minimum (or maximum) spanning forest by taking edges in order of
+    // weight, skipping any whose vertexes are already connected
+    pub fn spanning_tree<G: GraphView>(&self, graph: &G) -> Result<SpanningTree<G::Weight>> {
+        info!("Starting Kruskal Min Span Tree");
+
+        if graph.is_directed() {
+            return Err(Error::DirectedGraph);
+        }
+
+        let vertexes : Vec<usize> = graph.vertices().collect();
+        let mut sets = UnionFind::new();
+        for v in &vertexes {
+            sets.make_set(*v);
+        }
+
+        let mut tree_edges = Vec::<TreeEdge<G::Weight>>::new();
+        for (weight,v1,v2) in Kruskal::ordered_edges(graph,self.kind)? {
+            // once everything is one set there is nothing left to join
+            if sets.set_count() == 1 {
+                break;
+            }
+            if sets.union(v1,v2) {
+                tree_edges.push(TreeEdge::new(v1,v2,weight));
+            }
+        }
+
+        let tree = SpanningTree::from_edges(&vertexes,&tree_edges);
+        info!("Spanning forest has {} component(s)",tree.component_count());
+        Ok(tree)
+    }
+
+}
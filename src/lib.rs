@@ -0,0 +1,746 @@
+// graph algorithms (minimum spanning trees and shortest paths) along with the
+// loader for the "src dest weight" edge files they run on
+
+pub mod error;
+pub mod weight;
+pub mod graph;
+pub mod csr;
+pub mod input;
+pub mod loader;
+pub mod edge_policy;
+pub mod readers;
+pub mod fast_parser;
+pub mod spanning_tree;
+pub mod union_find;
+pub mod prim;
+pub mod kruskal;
+pub mod boruvka;
+pub mod k_best;
+pub mod uniqueness;
+pub mod dynamic_mst;
+pub mod bottleneck;
+pub mod clustering;
+pub mod paths;
+pub mod dijkstra;
+pub mod bellman_ford;
+pub mod all_pairs;
+pub mod export;
+
+pub use crate::error::{Error,Result};
+pub use crate::weight::Weight;
+pub use crate::graph::{Graph,Edge,GraphView};
+pub use crate::csr::CsrGraph;
+pub use crate::loader::{load_graph,load_csr_graph,read_graph,read_csr_graph,LoadOptions};
+pub use crate::readers::{GraphReader,InputFormat};
+pub use crate::edge_policy::{ParallelEdgePolicy,SelfLoopPolicy};
+pub use crate::prim::Prim;
+pub use crate::spanning_tree::{SpanningTree,TreeEdge,TreeComponent,TreeKind};
+
+
+/*
+ * the rest of this file sets up unit tests
+ * to run these, the command will be:
+ * cargo test --package prim -- --nocapture
+ * Note: 'prim' comes from Cargo.toml's 'name' key
+ */
+
+// use the attribute below for unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::union_find::UnionFind;
+    use crate::kruskal::Kruskal;
+    use crate::boruvka::Boruvka;
+    use crate::dijkstra::Dijkstra;
+    use crate::bellman_ford::BellmanFord;
+
+	fn setup_basic1() -> Graph {
+		let mut g = Graph::new();
+		assert_eq!(g.add_edge(1,2,1),Some(1));
+        assert_eq!(g.add_edge(1,3,1),Some(2));
+        assert_eq!(g.add_edge(2,3,1),Some(1));
+        assert_eq!(g.add_edge(2,4,1),Some(2));
+        assert_eq!(g.add_edge(3,4,1),Some(1));
+        assert_eq!(g.get_outgoing(1).unwrap(),&[Edge::new(2,1),Edge::new(3,1)]);
+		assert_eq!(g.get_outgoing(2).unwrap(),&[Edge::new(3,1),Edge::new(4,1)]);
+		assert_eq!(g.get_outgoing(3).unwrap(),&[Edge::new(4,1)]);
+		assert_eq!(g.get_outgoing(4).unwrap(),&[]);
+		g
+	} 
+
+	// a tree's edges as sorted (low, high) pairs, to compare trees whatever order and
+	// direction their edges came out in
+	fn edge_set(tree: &SpanningTree) -> Vec<(usize,usize)> {
+		let mut edges : Vec<(usize,usize)> = tree.edges.iter().map(|e| (e.src.min(e.dest),e.src.max(e.dest))).collect();
+		edges.sort();
+		edges
+	}
+
+	// a linear congruential generator giving numbers below n, so the randomized tests
+	// are the same every run
+	fn lcg(mut seed: u64) -> impl FnMut(u64) -> u64 {
+		move |n| { seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407); (seed >> 33) % n }
+	}
+
+    #[test]
+    fn basic() {
+		let mut g = Graph::new();
+		assert_eq!(g.create_vertex(&1),Some(1));
+		assert_eq!(g.create_vertex(&2),Some(2));
+		assert_eq!(g.add_edge(1,2,1),Some(1));
+		assert_eq!(g.get_vertexes(),vec!(1,2));
+		assert_eq!(g.create_vertex(&3),Some(3));
+		assert_eq!(g.add_edge(1,3,1),Some(2));
+		assert_eq!(g.add_edge(2,3,1),Some(1));
+		assert_eq!(g.get_vertexes(),vec!(1,2,3));
+		assert_eq!(g.add_edge(1,4,1),Some(3));
+		assert_eq!(g.get_vertexes(),vec!(1,2,3,4));
+		println!("{:?}",g);
+
+    }
+
+	#[test]
+	fn test_add() {
+		let mut g = Graph::new();
+		assert_eq!(g.add_edge(1,2,1),Some(1));
+		assert_eq!(g.get_outgoing(1).unwrap(),&[Edge::new(2,1)]);
+		assert_eq!(g.get_incoming(2).unwrap(),&[Edge::new(1,1)]);
+		assert_eq!(g.add_edge(1,3,1),Some(2));
+		assert_eq!(g.get_outgoing(1).unwrap(),&[Edge::new(2,1),Edge::new(3,1)]);
+		assert_eq!(g.get_incoming(2).unwrap(),&[Edge::new(1,1)]);
+	}
+
+	#[test]
+	fn test_add_del() {
+		let mut g = setup_basic1();
+		assert_eq!(g.get_outgoing(1).unwrap(),&[Edge::new(2,1),Edge::new(3,1)]);
+		assert_eq!(g.add_edge(1,2,1),Some(3));
+		assert_eq!(g.get_outgoing(1).unwrap(),&[Edge::new(2,1),Edge::new(3,1)]);
+		assert_eq!(g.get_outgoing(2).unwrap(),&[Edge::new(3,1),Edge::new(4,1)]);
+		assert_eq!(g.get_outgoing(3).unwrap(),&[Edge::new(4,1)]);
+		assert!(g.delete_edge(1,2,1).is_ok());
+		assert_eq!(g.get_outgoing(1).unwrap(),&[Edge::new(2,1),Edge::new(3,1)]);
+		assert!(g.delete_edge(1,2,1).is_ok());
+		assert_eq!(g.get_outgoing(1).unwrap(),&[Edge::new(3,1)]);
+		
+	}
+
+	#[test]
+	fn test_undirected() {
+		let mut g = Graph::new_undirected();
+		assert_eq!(g.add_edge(1,2,4),Some(1));
+		g.add_edge(2,3,1);
+		g.add_edge(3,3,2);
+		assert_eq!(g.edge_count(),3);
+		assert_eq!(g.get_outgoing(2).unwrap(),&[Edge::new(1,4),Edge::new(3,1)]);
+		assert_eq!(g.get_outgoing(3).unwrap(),&[Edge::new(2,1),Edge::new(3,2)]);
+		assert!(g.delete_edge(2,1,4).is_ok());
+		assert_eq!(g.edge_count(),2);
+		assert_eq!(g.get_outgoing(1).unwrap(),&[]);
+		assert_eq!(g.get_incoming(2).unwrap(),&[Edge::new(3,1)]);
+		assert!(g.delete_edge(3,3,2).is_ok());
+		assert!(matches!(g.delete_edge(1,2,4),Err(Error::UnknownEdge { src: 1, dest: 2 })));
+
+		// a spanning tree of a directed graph isn't defined
+		let g = setup_basic1();
+		assert!(matches!(Prim::new().spanning_tree(&g,1),Err(Error::DirectedGraph)));
+		assert!(matches!(Kruskal::new().spanning_tree(&g),Err(Error::DirectedGraph)));
+		assert!(matches!(Boruvka::new().spanning_tree(&g),Err(Error::DirectedGraph)));
+	}
+
+	#[test]
+	fn test_read_graph() {
+		let data = "4 3\n1 2 5\n2 3 -2\n4 1 7\n";
+		let undirected = read_graph(data.as_bytes(),&LoadOptions::default()).unwrap();
+		assert_eq!(undirected.get_vertexes(),vec!(1,2,3,4));
+		assert_eq!(undirected.get_outgoing(2).unwrap(),&[Edge::new(1,5),Edge::new(3,-2)]);
+		assert_eq!(undirected.edge_count(),3);
+		let g = read_graph(data.as_bytes(),&LoadOptions::new(true,true)).unwrap();
+		assert_eq!(g.get_outgoing(2).unwrap(),&[Edge::new(3,-2)]);
+		assert_eq!(g.edge_count(),3);
+
+		// blank lines are skipped, bad lines report where the problem is
+		assert!(read_graph::<i32,_>("2 1\n1 2 3\n\n".as_bytes(),&LoadOptions::default()).is_ok());
+		match read_graph::<i32,_>("2 2\n1 2 3\n1  x 4\n".as_bytes(),&LoadOptions::default()) {
+			Err(Error::Parse { line, column, .. }) => assert_eq!((line,column),(3,4)),
+			other => panic!("expected a parse error, got {:?}",other),
+		}
+		match read_graph::<i32,_>("2 2\n1 2\n".as_bytes(),&LoadOptions::default()) {
+			Err(Error::Parse { line, column, .. }) => assert_eq!((line,column),(2,1)),
+			other => panic!("expected a parse error, got {:?}",other),
+		}
+		assert!(matches!(g.get_outgoing(7),Err(Error::UnknownVertex(7))));
+		assert!(matches!(Prim::new().spanning_tree(&undirected,7),Err(Error::InvalidStartVertex(7))));
+	}
+
+	#[test]
+	fn test_read_header() {
+		let strict = LoadOptions::new(false,true);
+		// vertex 4 is declared with no edges
+		let g : Graph = read_graph("4 2\n1 2 5\n2 3 1\n4\n".as_bytes(),&strict).unwrap();
+		assert_eq!(g.get_vertexes(),vec!(1,2,3,4));
+		assert!(matches!(read_graph::<i32,_>("4 2\n1 2 5\n2 3 1\n".as_bytes(),&strict),
+			Err(Error::HeaderMismatch { expected: 4, found: 3, .. })));
+		assert!(matches!(read_graph::<i32,_>("3 3\n1 2 5\n2 3 1\n".as_bytes(),&strict),
+			Err(Error::HeaderMismatch { expected: 3, found: 2, .. })));
+		assert!(read_graph::<i32,_>("3 3\n1 2 5\n2 3 1\n".as_bytes(),&LoadOptions::default()).is_ok());
+		assert!(matches!(read_graph::<i32,_>("1 2 5\n2 3 1\n".as_bytes(),&strict),Err(Error::Parse { line: 1, .. })));
+	}
+
+	#[test]
+	fn test_fast_parser() {
+		let data = "6 5\n1 2 5\n 2\t3 -2 trailing\r\n\n4 1 -2147483648\n+6\n+5 4 +3\n4 5 3";
+		let expected : Graph = read_graph(data.as_bytes(),&LoadOptions::default()).unwrap();
+		for threads in [1,2,4,16] {
+			let mut options = LoadOptions::new(false,true);
+			options.fast = Some(fast_parser::FastEdgeListReader::new(threads,false));
+			let g = read_graph(data.as_bytes(),&options).unwrap();
+			assert_eq!(g.get_vertexes(),expected.get_vertexes());
+			for v in g.get_vertexes() {
+				assert_eq!(g.get_outgoing(v).unwrap(),expected.get_outgoing(v).unwrap());
+			}
+
+			// errors report the same line and column as the regex based reader
+			match read_graph::<i32,_>("3 2\n1 2 3\n\n2 3 4x\n".as_bytes(),&options) {
+				Err(Error::Parse { line, column, .. }) => assert_eq!((line,column),(4,5)),
+				other => panic!("expected a parse error, got {:?}",other),
+			}
+		}
+	}
+
+	#[test]
+	fn test_read_formats() {
+		let expected = vec!(Edge::new(1,5),Edge::new(3,-2));
+		let mut options = LoadOptions::new(false,true);
+
+		options.format = Some(InputFormat::Dimacs);
+		let g = read_graph("c test\np sp 4 2\na 1 2 5\na 2 3 -2\n".as_bytes(),&options).unwrap();
+		assert_eq!(g.get_outgoing(2).unwrap(),expected);
+		assert_eq!(g.get_vertexes(),vec!(1,2,3,4));
+
+		options.format = Some(InputFormat::Csv);
+		options.csv = readers::CsvReader { delimiter: '\t', src_column: 1, dest_column: 2, weight_column: Some(0), has_header: true };
+		let g = read_graph("w\tfrom\tto\n5\t1\t2\n-2\t 2\t3\n".as_bytes(),&options).unwrap();
+		assert_eq!(g.get_outgoing(2).unwrap(),expected);
+		match read_graph::<i32,_>("w\tfrom\tto\n5\t1\t2\n-2\t2\tx\n".as_bytes(),&options) {
+			Err(Error::Parse { line, column, .. }) => assert_eq!((line,column),(3,6)),
+			other => panic!("expected a parse error, got {:?}",other),
+		}
+
+		options.format = Some(InputFormat::MatrixMarket);
+		let g = read_graph("%%MatrixMarket matrix coordinate integer symmetric\n% test\n3 3 2\n2 1 5\n3 2 -2\n".as_bytes(),&options).unwrap();
+		assert_eq!(g.get_outgoing(2).unwrap(),expected);
+
+		options.format = Some(InputFormat::AdjacencyList);
+		let g = read_graph("1\t2,5\n2\t1,5\t3,-2\n3\t2,-2\n".as_bytes(),&options).unwrap();
+		assert_eq!(g.get_outgoing(2).unwrap(),expected);
+		assert_eq!(g.edge_count(),2);
+		// a parallel edge listed from both ends is still two edges
+		let g = read_graph::<i32,_>("1\t2,5\t2,5\n2\t1,5\t1,5\n".as_bytes(),&options).unwrap();
+		assert_eq!(g.edge_count(),2);
+		options.parallel_edges = ParallelEdgePolicy::KeepMin;
+		let g = read_graph::<i32,_>("1\t2,5\t2,5\n2\t1,5\t1,5\n".as_bytes(),&options).unwrap();
+		assert_eq!(g.edge_count(),1);
+	}
+
+	#[test]
+	fn test_weights() {
+		let data = "4 4\n1 2 0.5\n2 3 1.25\n1 3 2\n3 4 -1e-1\n";
+		let mut options = LoadOptions::default();
+		for fast in [None,Some(fast_parser::FastEdgeListReader::new(2,false))] {
+			options.fast = fast;
+			let g : Graph<f64> = read_graph(data.as_bytes(),&options).unwrap();
+			assert_eq!(g.get_outgoing(3).unwrap(),&[Edge::new(1,2.0),Edge::new(2,1.25),Edge::new(4,-0.1)]);
+			let tree = Prim::new().spanning_tree(&g,1).unwrap();
+			assert!((tree.total_weight() - 1.65).abs() < 1e-9);
+			assert_eq!(Dijkstra::new().shortest_path(&g,1,3).unwrap(),Some((vec!(1,2,3),1.75)));
+
+			// NaN has no ordering, so it isn't a weight
+			match read_graph::<f64,_>("2 1\n1 2 NaN\n".as_bytes(),&options) {
+				Err(Error::Parse { line, column, .. }) => assert_eq!((line,column),(2,5)),
+				other => panic!("expected a parse error, got {:?}",other),
+			}
+		}
+		assert!(read_graph::<i32,_>(data.as_bytes(),&options).is_err());
+
+		// weights that don't fit in an i32
+		let g : Graph<u64> = read_graph("2 1\n1 2 10000000000\n".as_bytes(),&LoadOptions::default()).unwrap();
+		assert_eq!(Kruskal::new().spanning_tree(&g).unwrap().total_weight(),10_000_000_000);
+		assert!(read_graph::<u64,_>("2 1\n1 2 -1\n".as_bytes(),&LoadOptions::default()).is_err());
+	}
+
+	#[test]
+	fn test_input_compression() {
+		use crate::input::{Compression,uncompressed_path};
+		use std::path::Path;
+		assert_eq!(Compression::from_magic(&[0x1f,0x8b,0x08]),Compression::Gzip);
+		assert_eq!(Compression::from_magic(&[0x28,0xb5,0x2f,0xfd,0x00]),Compression::Zstd);
+		assert_eq!(Compression::from_magic(b"\xfd7zXZ\x00\x00"),Compression::Xz);
+		assert_eq!(Compression::from_magic(b"500 2184\n"),Compression::None);
+		assert_eq!(uncompressed_path(Path::new("data/edges.mtx.gz")),Path::new("data/edges.mtx"));
+		assert_eq!(uncompressed_path(Path::new("edges.txt")),Path::new("edges.txt"));
+	}
+
+	#[test]
+	fn test_prim_tree() {
+		let mut g = Graph::new_undirected();
+		for (v1,v2,w) in [(1,2,1),(1,3,4),(1,4,3),(2,4,2),(3,4,5)] {
+			g.add_edge(v1,v2,w);
+		}
+		let mut p = Prim::new();
+		let tree = p.spanning_tree(&g,1).unwrap();
+		assert_eq!(tree.edges,vec!(TreeEdge::new(1,2,1),TreeEdge::new(2,4,2),TreeEdge::new(1,3,4)));
+		assert_eq!(tree.total_weight(),7);
+		assert_eq!(tree.to_graph().get_outgoing(2).unwrap(),&[Edge::new(1,1),Edge::new(4,2)]);
+		assert_eq!(tree.component_count(),1);
+	}
+
+	#[test]
+	fn test_export() {
+		let g : Graph = read_graph("3 2\n1 2 5\n2 3 -2\n".as_bytes(),&LoadOptions::default()).unwrap();
+		let tree = Prim::new().spanning_tree(&g,1).unwrap();
+		let mut out = Vec::<u8>::new();
+		export::export_graph(&mut out,&g,Some(&tree),export::ExportFormat::Dot).unwrap();
+		let dot = String::from_utf8(out).unwrap();
+		assert!(dot.starts_with("graph G {"));
+		assert!(dot.contains("1 -- 2 [label=\"5\", color=red, penwidth=3];"));
+		assert!(!dot.contains("2 -- 1"));
+
+		let g : Graph = read_graph("3 2\n1 2 5\n2 3 -2\n".as_bytes(),&LoadOptions::new(true,false)).unwrap();
+		let mut out = Vec::<u8>::new();
+		export::export_graph(&mut out,&g,None,export::ExportFormat::Json).unwrap();
+		let json = String::from_utf8(out).unwrap();
+		assert!(json.contains("\"directed\": true"));
+		assert!(json.contains("{\"source\": 1, \"target\": 2, \"weight\": 5, \"tree\": false}"));
+
+		// JSON has no infinity
+		let g : Graph<f64> = read_graph("2 1\n1 2 inf\n".as_bytes(),&LoadOptions::default()).unwrap();
+		let tree = Prim::new().spanning_tree(&g,1).unwrap();
+		let mut out = Vec::<u8>::new();
+		export::export_graph(&mut out,&g,Some(&tree),export::ExportFormat::Json).unwrap();
+		let json = String::from_utf8(out).unwrap();
+		assert!(json.contains("\"weight\": null"));
+		assert!(json.contains("\"tree_weight\": null"));
+		assert!(!json.contains("inf"));
+	}
+
+	#[test]
+	fn test_prim_forest() {
+		let mut g = Graph::new_undirected();
+		for (v1,v2,w) in [(1,2,3),(2,3,1),(4,5,2)] {
+			g.add_edge(v1,v2,w);
+		}
+		g.create_vertex(&6);
+		let mut p = Prim::new();
+		for _ in 0..2 {
+			let tree = p.spanning_tree(&g,4).unwrap();
+			assert_eq!(tree.component_count(),3);
+			assert_eq!(tree.total_weight(),6);
+			assert_eq!(tree.components[0],TreeComponent { root: 4, vertices: vec!(4,5), total_weight: 2 });
+			assert_eq!(tree.components[1],TreeComponent { root: 1, vertices: vec!(1,2,3), total_weight: 4 });
+			assert_eq!(tree.components[2],TreeComponent { root: 6, vertices: vec!(6), total_weight: 0 });
+		}
+
+		// the same instance gives the same tree every time
+		let kruskal = Kruskal::new();
+		for _ in 0..2 {
+			let tree = kruskal.spanning_tree(&g).unwrap();
+			assert_eq!(tree.component_count(),3);
+			assert_eq!(tree.total_weight(),6);
+			assert_eq!(tree.components[0],TreeComponent { root: 1, vertices: vec!(1,2,3), total_weight: 4 });
+		}
+
+		for threads in [1,3] {
+			let tree = Boruvka::with_threads(threads).spanning_tree(&g).unwrap();
+			assert_eq!(tree.component_count(),3);
+			assert_eq!(tree.total_weight(),6);
+			assert_eq!(tree.components[1],TreeComponent { root: 4, vertices: vec!(4,5), total_weight: 2 });
+		}
+	}
+
+	#[test]
+	fn test_max_and_k_best() {
+		let mut g = Graph::new_undirected();
+		for (v1,v2,w) in [(1,2,3),(2,3,1),(3,1,4),(3,4,2),(5,6,1)] {
+			g.add_edge(v1,v2,w);
+		}
+		assert_eq!(Prim::maximum().spanning_tree(&g,1).unwrap().total_weight(),10);
+		assert_eq!(Kruskal::maximum().spanning_tree(&g).unwrap().total_weight(),10);
+		let boruvka = Boruvka { kind: TreeKind::Maximum, ..Boruvka::with_threads(2) };
+		assert_eq!(boruvka.spanning_tree(&g).unwrap().total_weight(),10);
+
+		let weights = |trees: Vec<SpanningTree>| trees.iter().map(|t| t.total_weight()).collect::<Vec<i32>>();
+		assert_eq!(weights(k_best::KBestTrees::new().k_best(&g,2).unwrap()),vec!(7,8));
+		assert_eq!(weights(k_best::KBestTrees::new().k_best(&g,5).unwrap()),vec!(7,8,10));
+		assert_eq!(weights(k_best::KBestTrees::maximum().k_best(&g,5).unwrap()),vec!(10,8,7));
+		let trees = k_best::KBestTrees::new().k_best(&g,1).unwrap();
+		assert_eq!(trees[0].component_count(),2);
+		assert_eq!(trees[0].total_weight(),Kruskal::new().spanning_tree(&g).unwrap().total_weight());
+
+		// all 16 spanning trees of K4, each once and in order
+		let mut k4 = Graph::new_undirected();
+		for (v1,v2,w) in [(1,2,1),(1,3,2),(1,4,3),(2,3,4),(2,4,5),(3,4,6)] {
+			k4.add_edge(v1,v2,w);
+		}
+		let trees = k_best::KBestTrees::new().k_best(&k4,20).unwrap();
+		assert_eq!(trees.len(),16);
+		let mut edge_sets : Vec<Vec<(usize,usize)>> = trees.iter().map(edge_set).collect();
+		assert!(trees.windows(2).all(|t| t[0].total_weight() <= t[1].total_weight()));
+		edge_sets.sort();
+		edge_sets.dedup();
+		assert_eq!(edge_sets.len(),16);
+	}
+
+	#[test]
+	fn test_tie_break() {
+		// a square of equal weight edges has four minimum spanning trees
+		let mut g = Graph::new_undirected();
+		for (v1,v2,w) in [(3,4,1),(1,2,1),(4,1,1),(2,3,1),(1,3,2)] {
+			g.add_edge(v1,v2,w);
+		}
+		let expected = vec!((1,2),(1,4),(2,3));
+		for start in 1..=4 {
+			assert_eq!(edge_set(&Prim::new().spanning_tree(&g,start).unwrap()),expected);
+		}
+		let tree = Kruskal::new().spanning_tree(&g).unwrap();
+		assert_eq!(edge_set(&tree),expected);
+		assert_eq!(edge_set(&Boruvka::with_threads(2).spanning_tree(&g).unwrap()),expected);
+
+		let report = uniqueness::Uniqueness::check(&g,&tree,TreeKind::Minimum).unwrap();
+		assert!(!report.is_unique());
+		assert_eq!(report.alternatives.len(),1);
+		assert_eq!(report.alternatives[0].edge,TreeEdge::new(3,4,1));
+		assert_eq!(report.alternatives[0].replaces.len(),3);
+
+		// every maximum tree has the diagonal, but the square edges left out could replace those taken
+		let tree = Kruskal::maximum().spanning_tree(&g).unwrap();
+		assert_eq!(edge_set(&tree),vec!((1,2),(1,3),(1,4)));
+		let report = uniqueness::Uniqueness::check(&g,&tree,TreeKind::Maximum).unwrap();
+		assert_eq!(report.alternatives.len(),2);
+
+		g.delete_edge(3,4,1).unwrap();
+		let tree = Prim::new().spanning_tree(&g,1).unwrap();
+		assert!(uniqueness::Uniqueness::check(&g,&tree,TreeKind::Minimum).unwrap().is_unique());
+	}
+
+	#[test]
+	fn test_dynamic_mst() {
+		let mut g = Graph::new_undirected();
+		for (v1,v2,w) in [(1,2,3),(2,3,1),(3,1,4),(3,4,2)] {
+			g.add_edge(v1,v2,w);
+		}
+		let mut mst = dynamic_mst::DynamicMst::new(g,TreeKind::Minimum).unwrap();
+		assert_eq!(mst.total_weight(),6);
+
+		// 1 - 3 at 1 closes the cycle 1 2 3 and replaces its worst edge
+		let delta = mst.insert_edge(1,3,1).unwrap();
+		assert_eq!(delta.removed,vec!(TreeEdge::new(1,2,3)));
+		assert_eq!(delta.added,vec!(TreeEdge::new(1,3,1)));
+		assert_eq!((delta.old_weight,delta.new_weight),(6,4));
+		assert!(mst.insert_edge(2,4,5).unwrap().is_empty());
+		let delta = mst.delete_edge(3,4,2).unwrap();
+		assert_eq!(delta.added,vec!(TreeEdge::new(2,4,5)));
+		assert_eq!(delta.new_weight,7);
+		// a new vertex joins the forest, deleting its only edge splits it off again
+		assert_eq!(mst.insert_edge(5,6,2).unwrap().added.len(),1);
+		assert_eq!(mst.spanning_tree().component_count(),2);
+		assert_eq!(mst.delete_edge(5,6,2).unwrap().new_weight,7);
+		assert!(matches!(mst.delete_edge(1,2,9),Err(Error::UnknownEdge { src: 1, dest: 2 })));
+
+		// random edits always agree with rebuilding the tree
+		let mut next = lcg(7);
+		let mut edges = Vec::<(usize,usize,i32)>::new();
+		for kind in [TreeKind::Minimum,TreeKind::Maximum] {
+			let mut mst = dynamic_mst::DynamicMst::new(Graph::new_undirected(),kind).unwrap();
+			for _ in 0..300 {
+				if edges.is_empty() || next(3) > 0 {
+					let e = (next(12) as usize,next(12) as usize,next(6) as i32);
+					mst.insert_edge(e.0,e.1,e.2).unwrap();
+					edges.push(e);
+				}
+				else {
+					let (v1,v2,w) = edges.swap_remove(next(edges.len() as u64) as usize);
+					mst.delete_edge(v1,v2,w).unwrap();
+				}
+				let tree = Kruskal { kind }.spanning_tree(mst.graph()).unwrap();
+				assert_eq!(mst.total_weight(),tree.total_weight());
+				assert_eq!(mst.spanning_tree().component_count(),tree.component_count());
+			}
+			edges.clear();
+		}
+	}
+
+	#[test]
+	fn test_bottleneck() {
+		let mut next = lcg(11);
+		let mut g = Graph::new_undirected();
+		for _ in 0..60 {
+			g.add_edge(next(25) as usize,next(25) as usize,next(20) as i32 - 5);
+		}
+		g.create_vertex(&30);
+
+		for kind in [TreeKind::Minimum,TreeKind::Maximum] {
+			let edges = Kruskal::ordered_edges(&g,kind).unwrap();
+			// the weight of the edge that first joins v1 and v2 when taken in tree order
+			let minimax = |v1: usize, v2: usize| {
+				let mut sets = UnionFind::new();
+				for v in g.get_vertexes() {
+					sets.make_set(v);
+				}
+				edges.iter().find(|(_,a,b)| { sets.union(*a,*b); sets.find(v1) == sets.find(v2) }).map(|e| e.0)
+			};
+			for tree in [Prim { kind, ..Prim::new() }.spanning_tree(&g,0).unwrap(),Kruskal { kind }.spanning_tree(&g).unwrap()] {
+				let query = bottleneck::BottleneckQuery::new(&tree,kind);
+				for v1 in g.get_vertexes() {
+					for v2 in g.get_vertexes() {
+						let expected = if v1 == v2 { None } else { minimax(v1,v2) };
+						assert_eq!(query.query(v1,v2).unwrap(),expected,"{} - {}",v1,v2);
+					}
+				}
+				assert!(query.query(0,99).is_err());
+			}
+		}
+
+		let queries = bottleneck::read_queries(&mut "# pairs\n1 2\n\n 3 4 ignored\n".as_bytes()).unwrap();
+		assert_eq!(queries,vec!((1,2),(3,4)));
+		assert!(matches!(bottleneck::read_queries(&mut "1 2\n3 x\n".as_bytes()),Err(Error::Parse { line: 2, column: 3, .. })));
+	}
+
+	#[test]
+	fn test_clustering() {
+		let mut g = Graph::new_undirected();
+		for (v1,v2,w) in [(1,2,1),(2,3,2),(3,1,9),(3,4,7),(4,5,1),(5,6,3),(4,6,2),(7,8,4)] {
+			g.add_edge(v1,v2,w);
+		}
+		g.create_vertex(&9);
+		for tree in [Prim::new().spanning_tree(&g,1).unwrap(),Kruskal::new().spanning_tree(&g).unwrap()] {
+			let clustering = clustering::Clustering::new(&tree);
+			assert_eq!(clustering.vertices,vec!(1,2,3,4,5,6,7,8,9));
+			assert_eq!(clustering.merges.len(),6);
+			assert_eq!(clustering.merges[0],clustering::ClusterMerge { left: 0, right: 1, height: 1, size: 2 });
+			assert_eq!(clustering.merges[1],clustering::ClusterMerge { left: 3, right: 4, height: 1, size: 2 });
+			assert_eq!(clustering.merges[2],clustering::ClusterMerge { left: 2, right: 9, height: 2, size: 3 });
+			assert_eq!(clustering.merges[5],clustering::ClusterMerge { left: 11, right: 12, height: 7, size: 6 });
+			let heights : Vec<i32> = clustering.merges.iter().map(|m| m.height).collect();
+			assert!(heights.windows(2).all(|h| h[0] <= h[1]));
+
+			assert_eq!(clustering.clusters(4),vec!(vec!(1,2,3),vec!(4,5,6),vec!(7,8),vec!(9)));
+			assert_eq!(clustering.spacing(4),Some(7));
+			assert_eq!(clustering.clusters(6),vec!(vec!(1,2,3),vec!(4,5),vec!(6),vec!(7),vec!(8),vec!(9)));
+			assert_eq!(clustering.spacing(6),Some(2));
+			// the three components are never joined
+			assert_eq!(clustering.clusters(1).len(),3);
+			assert_eq!(clustering.spacing(3),None);
+			assert_eq!(clustering.clusters(20).len(),9);
+		}
+	}
+
+	#[test]
+	fn test_csr_graph() {
+		let data = "6 5\n1 2 3\n2 3 1\n4 5 2\n3 1 -1\n6\n1 3 4\n";
+		let g = read_graph(data.as_bytes(),&LoadOptions::default()).unwrap();
+		let options = LoadOptions { fast: Some(fast_parser::FastEdgeListReader::new(2,false)), ..LoadOptions::default() };
+		for csr in [CsrGraph::from_graph(&g),options.fast.as_ref().unwrap().parse_csr(data.as_bytes(),&options).unwrap()] {
+			assert!(csr.vertices().eq(g.vertices()));
+			assert_eq!(csr.edge_count(),5);
+			for v in g.get_vertexes() {
+				assert!(csr.neighbors(v).unwrap().eq(g.neighbors(v).unwrap()));
+			}
+			assert!(csr.neighbors(7).is_err());
+			assert!(csr.has_negative_edges());
+
+			assert_eq!(Prim::new().spanning_tree(&csr,1).unwrap().total_weight(),2);
+			assert_eq!(Kruskal::new().spanning_tree(&csr).unwrap().total_weight(),2);
+			assert_eq!(Dijkstra::new().shortest_path(&csr,1,3).unwrap(),Some((vec!(1,3),-1)));
+		}
+
+		// every view keeps each copy of a parallel edge
+		let data = "2 3\n1 2 3\n1 2 3\n2 1 3\n";
+		let g : Graph = read_graph(data.as_bytes(),&LoadOptions::default()).unwrap();
+		let (from_graph, built) = (CsrGraph::from_graph(&g),options.fast.as_ref().unwrap().parse_csr(data.as_bytes(),&options).unwrap());
+		for v in [1,2] {
+			assert_eq!(from_graph.neighbors(v).unwrap().count(),3);
+			assert!(from_graph.neighbors(v).unwrap().eq(built.neighbors(v).unwrap()));
+			assert!(g.neighbors(v).unwrap().eq(built.neighbors(v).unwrap()));
+		}
+		assert_eq!(from_graph.edge_count(),built.edge_count());
+
+		// the other formats are read straight into a CSR graph too
+		for (format, data) in [(InputFormat::MatrixMarket,"%%MatrixMarket matrix coordinate integer symmetric\n3 3 2\n2 1 5\n3 2 -2\n"),(InputFormat::AdjacencyList,"1\t2,5\n2\t1,5\t3,-2\n3\t2,-2\n")] {
+			let options = LoadOptions { directed: true, format: Some(format), ..LoadOptions::default() };
+			let g : Graph = read_graph(data.as_bytes(),&options).unwrap();
+			let csr : CsrGraph = read_csr_graph(data.as_bytes(),&options).unwrap();
+			assert_eq!(csr.edge_count(),g.edge_count());
+			for v in g.get_vertexes() {
+				assert!(csr.neighbors(v).unwrap().eq(g.neighbors(v).unwrap()));
+			}
+		}
+	}
+
+	#[test]
+	fn test_edge_policy() {
+		let clean = "3 3\n1 2 3\n2 3 1\n3 1 4\n";
+		let noisy = "3 7\n1 2 3\n2 3 1\n2 1 5\n3 3 2\n3 1 4\n1 2 3\n3 2 6\n";
+		let mut options = LoadOptions::default();
+		let g : Graph = read_graph(clean.as_bytes(),&options).unwrap();
+		let expected = Kruskal::new().spanning_tree(&g).unwrap().total_weight();
+
+		// the MST is the same whatever the duplicates
+		let g : Graph = read_graph(noisy.as_bytes(),&options).unwrap();
+		assert_eq!(g.edge_count(),7);
+		assert_eq!(Prim::new().spanning_tree(&g,1).unwrap().total_weight(),expected);
+		assert_eq!(Kruskal::new().spanning_tree(&g).unwrap().total_weight(),expected);
+		assert_eq!(Boruvka::new().spanning_tree(&g).unwrap().total_weight(),expected);
+
+		options.self_loops = SelfLoopPolicy::Drop;
+		for (policy, weight_1_2, weight_2_3) in [(ParallelEdgePolicy::KeepMin,3,1),(ParallelEdgePolicy::KeepMax,5,6),(ParallelEdgePolicy::Sum,11,7)] {
+			options.parallel_edges = policy;
+			let g : Graph = read_graph(noisy.as_bytes(),&options).unwrap();
+			assert_eq!(g.edge_count(),3);
+			assert_eq!(g.get_outgoing(2).unwrap(),vec!(Edge::new(1,weight_1_2),Edge::new(3,weight_2_3)));
+			assert_eq!(g.get_outgoing(3).unwrap().len(),2);
+
+			options.fast = Some(fast_parser::FastEdgeListReader::new(2,false));
+			let csr = options.fast.as_ref().unwrap().parse_csr::<i32>(noisy.as_bytes(),&options).unwrap();
+			assert_eq!(csr.edge_count(),3);
+			assert!(csr.neighbors(2).unwrap().eq(g.neighbors(2).unwrap()));
+			options.fast = None;
+		}
+		options.parallel_edges = ParallelEdgePolicy::KeepMin;
+		let g : Graph = read_graph(noisy.as_bytes(),&options).unwrap();
+		assert_eq!(Prim::new().spanning_tree(&g,1).unwrap().total_weight(),expected);
+
+		options.parallel_edges = ParallelEdgePolicy::Reject;
+		assert!(matches!(read_graph::<i32,_>(noisy.as_bytes(),&options),Err(Error::ParallelEdge { src: 2, dest: 1 })));
+		// the same pair in both directions is only parallel in an undirected graph
+		options.directed = true;
+		assert!(matches!(read_graph::<i32,_>(noisy.as_bytes(),&options),Err(Error::ParallelEdge { src: 1, dest: 2 })));
+		options.directed = false;
+		options.parallel_edges = ParallelEdgePolicy::KeepAll;
+		options.self_loops = SelfLoopPolicy::Reject;
+		assert!(matches!(read_graph::<i32,_>(noisy.as_bytes(),&options),Err(Error::SelfLoop(3))));
+	}
+
+	// an adjacency store outside the crate only needs GraphView to use the algorithms
+	struct AdjacencyVec(Vec<Vec<(usize,i32)>>);
+
+	impl GraphView for AdjacencyVec {
+		type Weight = i32;
+		fn vertex_count(&self) -> usize { self.0.len() }
+		fn is_directed(&self) -> bool { false }
+		fn vertices(&self) -> impl Iterator<Item=usize> + '_ { 0..self.0.len() }
+		fn contains_vertex(&self, vertex: usize) -> bool { vertex < self.0.len() }
+		fn neighbors(&self, vertex: usize) -> Result<impl Iterator<Item=(usize,i32)> + '_> {
+			Ok(self.0.get(vertex).ok_or(Error::UnknownVertex(vertex))?.iter().copied())
+		}
+	}
+
+	#[test]
+	fn test_graph_view() {
+		let g = AdjacencyVec(vec!(vec!((1,4),(2,1)),vec!((0,4),(2,2)),vec!((0,1),(1,2)),vec!()));
+		assert!(!g.has_negative_edges());
+		let tree = Prim::new().spanning_tree(&g,0).unwrap();
+		assert_eq!(tree.total_weight(),3);
+		assert_eq!(tree.component_count(),2);
+		assert_eq!(Kruskal::new().spanning_tree(&g).unwrap().total_weight(),3);
+		assert_eq!(Dijkstra::new().shortest_path(&g,0,1).unwrap(),Some((vec!(0,2,1),3)));
+		assert_eq!(BellmanFord::new().shortest_paths(&g,1).unwrap().distance.get(&0),Some(&3));
+	}
+
+	#[test]
+	fn test_union_find() {
+		let mut sets = UnionFind::new();
+		for id in 1..=5 {
+			sets.make_set(id);
+		}
+		assert!(sets.union(1,2));
+		assert!(sets.union(3,4));
+		assert!(!sets.union(2,1));
+		assert!(sets.union(2,4));
+		assert_eq!(sets.find(3),sets.find(1));
+		assert_ne!(sets.find(5),sets.find(1));
+		assert_eq!(sets.find(6),None);
+		assert_eq!(sets.set_count(),2);
+	}
+
+	#[test]
+	fn test_dijkstra() {
+		let mut g = setup_basic1();
+		g.add_edge(1,4,5);
+		g.create_vertex(&5);
+		let paths = Dijkstra::new().shortest_paths(&g,1).unwrap();
+		assert_eq!(paths.distance.get(&4),Some(&2));
+		assert_eq!(paths.distance.get(&3),Some(&1));
+		assert_eq!(paths.predecessor.get(&3),Some(&1));
+		assert_eq!(paths.predecessor.get(&1),None);
+		assert!(!paths.is_reachable(5));
+		assert_eq!(paths.path_to(4),Some(vec!(1,2,4)));
+
+		// one instance can run any number of searches
+		let mut d = Dijkstra::new();
+		assert_eq!(d.shortest_path(&g,2,4).unwrap(),Some((vec!(2,4),1)));
+		assert_eq!(d.shortest_path(&g,1,1).unwrap(),Some((vec!(1),0)));
+		assert_eq!(d.shortest_path(&g,1,5).unwrap(),None);
+		assert_eq!(d.shortest_paths(&g,1).unwrap().distance,paths.distance);
+	}
+
+	#[test]
+	fn test_bellman_ford() {
+		let mut g = setup_basic1();
+		g.add_edge(1,4,-3);
+		let mut bf = BellmanFord::new();
+		let paths = bf.shortest_paths(&g,1).unwrap();
+		assert_eq!(paths.distance.get(&4),Some(&-3));
+		assert_eq!(paths.path_to(3),Some(vec!(1,3)));
+		// nothing from the first search carries over to the next
+		let paths = bf.shortest_paths(&g,3).unwrap();
+		assert_eq!(paths.distance,std::collections::BTreeMap::from([(3,0),(4,1)]));
+		assert_eq!(paths.path_to(4),Some(vec!(3,4)));
+		assert_eq!(paths.path_to(1),None);
+
+		g.add_edge(4,2,1);
+		g.add_edge(2,1,1);
+		let cycle = match BellmanFord::new().shortest_paths(&g,1) {
+			Err(Error::NegativeCycle(cycle)) => cycle,
+			other => panic!("expected a negative cycle, got {:?}",other),
+		};
+		assert_eq!(cycle.vertices.first(),cycle.vertices.last());
+		let mut on_cycle = cycle.vertices.clone();
+		on_cycle.sort();
+		on_cycle.dedup();
+		assert_eq!(on_cycle,vec!(1,2,4));
+	}
+
+	#[test]
+	fn test_all_pairs() {
+		let mut g = setup_basic1();
+		g.add_edge(1,4,-3);
+		g.add_edge(4,3,-1);
+		g.create_vertex(&5);
+		for matrix in [all_pairs::floyd_warshall(&g).unwrap(),all_pairs::johnson(&g).unwrap()] {
+			assert_eq!(matrix.get(1,4),Some(-3));
+			assert_eq!(matrix.get(1,3),Some(-4));
+			assert_eq!(matrix.get(2,3),Some(0));
+			assert_eq!(matrix.get(3,1),None);
+			assert_eq!(matrix.get(5,5),Some(0));
+			assert_eq!(matrix.min_shortest_path(),Some((1,3,-4)));
+		}
+
+		g.add_edge(3,1,1);
+		assert!(all_pairs::floyd_warshall(&g).is_err());
+		assert!(all_pairs::johnson(&g).is_err());
+
+		// a cycle that only goes negative through float rounding must not panic
+		let mut g = Graph::<f64>::new();
+		for (v1,v2,w) in [(1,2,99.2),(2,3,7.73),(3,1,-106.93),(1,3,200.0),(2,1,300.0)] {
+			g.add_edge(v1,v2,w);
+		}
+		// the relaxed sums round just below zero, so the cycle is reported
+		match all_pairs::floyd_warshall(&g) {
+			Err(Error::NegativeCycle(cycle)) => assert_eq!(cycle.vertices,vec![3,1,2,3]),
+			other => panic!("expected a negative cycle, got {:?}",other.map(|m| m.min_shortest_path())),
+		}
+	}
+
+
+ }
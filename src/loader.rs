@@ -0,0 +1,88 @@
+use std::path::Path;
+use std::io::BufRead;
+use log::info;
+use crate::graph::Graph;
+use crate::error::Result;
+use crate::input::{open_input,uncompressed_path};
+use crate::readers::{GraphReader,InputFormat,CsvReader};
+use crate::fast_parser::FastEdgeListReader;
+use crate::csr::{CsrGraph,CsrBuilder};
+use crate::weight::Weight;
+use crate::edge_policy::{ParallelEdgePolicy,SelfLoopPolicy};
+
+
+#[derive(Debug,Clone,Default)]
+pub struct LoadOptions {
+    // add each edge line as a single directed edge rather than in both directions
+    pub directed: bool,
+    // a header that doesn't match the data is an error instead of a warning
+    pub strict: bool,
+    // input format, when None it is picked from the file extension
+    pub format: Option<InputFormat>,
+    // column layout used for csv/tsv input
+    pub csv: CsvReader,
+    // use the byte level FastEdgeListReader for edge list input
+    pub fast: Option<FastEdgeListReader>,
+    // how repeated edges between the same vertexes and self loops are handled
+    pub parallel_edges: ParallelEdgePolicy,
+    pub self_loops: SelfLoopPolicy,
+}
+
+impl LoadOptions {
+    pub fn new(directed: bool, strict: bool) -> Self {
+        LoadOptions { directed, strict, ..Default::default() }
+    }
+}
+
+
+// reads a graph in the format given by the options (default is "src dest weight" lines
+// following a "vertex_count edge_count" header)
+pub fn read_graph<W: Weight, R: BufRead>(mut reader: R, options: &LoadOptions) -> Result<Graph<W>> {
+    let format = options.format.unwrap_or(InputFormat::EdgeList);
+    if let (InputFormat::EdgeList, Some(fast)) = (format,&options.fast) {
+        return fast.read(&mut reader,options);
+    }
+    format.reader(options).read(&mut reader,options)
+}
+
+// loads a graph from a file ("-" for stdin) which may be gzip, zstd or xz compressed
+pub fn load_graph<W: Weight>(filename: &str, options: &LoadOptions) -> Result<Graph<W>> {
+  // Create a path to the desired file
+    let path = Path::new(filename);
+
+    // the format comes from the extension underneath any compression extension
+    let format = options.format.unwrap_or_else(|| InputFormat::from_path(&uncompressed_path(path)));
+    info!("Reading {} as {:?}",filename,format);
+
+    if let (InputFormat::EdgeList, Some(fast)) = (format,&options.fast) {
+        return fast.load(filename,options);
+    }
+
+    let mut reader = open_input(filename)?;
+    format.reader(options).read(&mut reader,options)
+}
+
+// same as read_graph, but builds a CsrGraph without going through a Graph
+pub fn read_csr_graph<W: Weight, R: BufRead>(mut reader: R, options: &LoadOptions) -> Result<CsrGraph<W>> {
+    let format = options.format.unwrap_or(InputFormat::EdgeList);
+    let mut builder = CsrBuilder::new(options);
+    let header = match (format,&options.fast) {
+        (InputFormat::EdgeList, Some(fast)) => fast.read_into(&mut reader,options,&mut builder)?,
+        _ => format.reader(options).read_into(&mut reader,options,&mut builder)?,
+    };
+    builder.finish(header)
+}
+
+// same as load_graph, but builds a CsrGraph without going through a Graph
+pub fn load_csr_graph<W: Weight>(filename: &str, options: &LoadOptions) -> Result<CsrGraph<W>> {
+    let format = options.format.unwrap_or_else(|| InputFormat::from_path(&uncompressed_path(Path::new(filename))));
+    info!("Reading {} as {:?} into a CSR graph",filename,format);
+    if let (InputFormat::EdgeList, Some(fast)) = (format,&options.fast) {
+        return fast.load_csr(filename,options);
+    }
+
+    let mut reader = open_input(filename)?;
+    let mut builder = CsrBuilder::new(options);
+    let header = format.reader(options).read_into(&mut reader,options,&mut builder)?;
+    builder.finish(header)
+}
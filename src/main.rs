@@ -1,154 +1,243 @@
-use std::path::Path;
-use std::fs::File;
-use std::io::{prelude::*, BufReader};
-use regex::Regex;
-use log::{ info , error ,debug, warn,trace };
-
-mod graph;
-use crate::graph::Graph;
+use std::process;
+use log::{ info , error ,debug };
+
+use prim::{load_graph,load_csr_graph,LoadOptions,Error,Result,GraphView,SpanningTree,TreeKind,Weight};
+use prim::prim::Prim;
+use prim::kruskal::Kruskal;
+use prim::boruvka::Boruvka;
+use prim::k_best::KBestTrees;
+use prim::uniqueness::Uniqueness;
+use prim::bottleneck::{BottleneckQuery,read_queries};
+use prim::clustering::Clustering;
+use prim::input::open_input;
+use prim::dijkstra::Dijkstra;
+use prim::bellman_ford::BellmanFord;
+use prim::all_pairs::{self,DistanceMatrix};
+use prim::export::export_to_file;
+use prim::paths::ShortestPaths;
 mod cmd_line;
-use crate::cmd_line::CommandArgs;
-mod prim;
-use crate::prim::Prim;
+use crate::cmd_line::{CommandArgs,Mode,MstAlgorithm,WeightType};
 
 
 
-fn main() {
+fn print_path<W: Weight>(path: Option<(Vec<usize>,W)>, src: usize, dst: usize) {
+    match path {
+        Some((path,distance)) => {
+            let path_str : Vec<String> = path.iter().map(|v| v.to_string()).collect();
+            println!("{}",distance);
+            println!("{}",path_str.join(" -> "));
+        },
+        None => println!("Vertex {} is not reachable from {}",dst,src),
+    }
+}
 
-    env_logger::init();
+fn print_paths<W: Weight>(paths: &ShortestPaths<W>, target: Option<usize>) {
+    if let Some(target) = target {
+        let path = paths.path_to(target).map(|p| (p,paths.distance[&target]));
+        print_path(path,paths.source,target);
+        return;
+    }
 
-    let cmd_line = CommandArgs::new();
+    for (v, distance) in &paths.distance {
+        match paths.predecessor.get(v) {
+            Some(p) => println!("{} {} (via {})",v,distance,p),
+            None => println!("{} {}",v,distance),
+        }
+    }
+}
 
-//    println!("Hello, {:?}!",cmd_line);
+fn print_min_shortest_path<W: Weight>(matrix: &DistanceMatrix<W>) {
+    match matrix.min_shortest_path() {
+        Some((src,dest,distance)) => println!("{}\n{} -> {}",distance,src,dest),
+        None => println!("No paths between distinct vertexes"),
+    }
+}
 
-    info!("Calulating shortest path from Vertex {} to all other vertexes",cmd_line.start_vertex);
-  // Create a path to the desired file
-    let path = Path::new(&cmd_line.filename);
-    let display = path.display();
+fn print_tree<W: Weight>(tree: &SpanningTree<W>) {
+    for e in &tree.edges {
+        debug!("Tree edge {} - {} wgt: {}",e.src,e.dest,e.weight);
+    }
+    println!("{}",tree.total_weight());
+    if tree.is_forest() {
+        println!("{} components",tree.component_count());
+        for c in &tree.components {
+            println!("component root {} vertexes {} weight {}",c.root,c.vertices.len(),c.total_weight);
+        }
+    }
+}
 
+fn print_uniqueness<W: Weight>(uniqueness: &Uniqueness<W>) {
+    if uniqueness.is_unique() {
+        println!("Spanning tree is unique");
+        return;
+    }
+    println!("Spanning tree is not unique, {} edge(s) are interchangeable",uniqueness.alternatives.len());
+    for a in &uniqueness.alternatives {
+        let replaces : Vec<String> = a.replaces.iter().map(|e| format!("{} - {}",e.src,e.dest)).collect();
+        println!("{} - {} wgt: {} can replace {}",a.edge.src,a.edge.dest,a.edge.weight,replaces.join(", "));
+    }
+}
 
-    // Open the path in read-only mode, returns `io::Result<File>`
-    let file = match File::open(&path) {
-        Err(why) => panic!("couldn't open {}: {}", display, why),
-        Ok(file) => file,
+// prints the spanning tree asked for on the command line (or the k best trees with
+// --k-best) and returns the best one
+fn run_mst<G: GraphView>(cmd_line: &CommandArgs, g: &G) -> Result<Option<SpanningTree<G::Weight>>> {
+    let kind = if cmd_line.maximum { TreeKind::Maximum } else { TreeKind::Minimum };
+    let tree = if let Some(k) = cmd_line.k_best {
+        let trees = KBestTrees { kind }.k_best(g,k)?;
+        for tree in &trees {
+            print_tree(tree);
+        }
+        trees.into_iter().next()
+    }
+    else {
+        let tree = match cmd_line.algorithm {
+            MstAlgorithm::Prim => Prim { kind, ..Prim::new() }.spanning_tree(g,cmd_line.start_vertex)?,
+            MstAlgorithm::Kruskal => Kruskal { kind }.spanning_tree(g)?,
+            MstAlgorithm::Boruvka => Boruvka { kind, ..Boruvka::with_threads(cmd_line.threads) }.spanning_tree(g)?,
+        };
+        print_tree(&tree);
+        if cmd_line.check_unique {
+            print_uniqueness(&Uniqueness::check(g,&tree,kind)?);
+        }
+        Some(tree)
     };
 
-    let mut reader = BufReader::new(file);
-
-	let mut g = Graph::new();
-	let mut p = Prim::new();
-
-    // read the first line
-    let mut line = String::new();
-    let _len = reader.read_line(&mut line).unwrap();
+    if let (Some(query_file), Some(tree)) = (&cmd_line.bottleneck_queries,&tree) {
+        let queries = read_queries(&mut open_input(query_file)?)?;
+        info!("Answering {} bottleneck queries from {}",queries.len(),query_file);
+        let bottleneck = BottleneckQuery::new(tree,kind);
+        for (v1, v2) in queries {
+            match bottleneck.query(v1,v2)? {
+                Some(weight) => println!("{} {} {}",v1,v2,weight),
+                None => println!("{} {} none",v1,v2),
+            }
+        }
+    }
 
-	let mut _count = 0;
-    for line in reader.lines() {
-		_count += 1;	
-		let line_data = line.unwrap();
- //       println!("Processing {}",line_data);
+    if let Some(tree) = tree.as_ref().filter(|_| cmd_line.clusters.is_some() || cmd_line.dendrogram) {
+        let clustering = Clustering::new(tree);
+        if cmd_line.dendrogram {
+            for m in &clustering.merges {
+                println!("{} {} {} {}",m.left,m.right,m.height,m.size);
+            }
+        }
+        if let Some(k) = cmd_line.clusters {
+            match clustering.spacing(k) {
+                Some(spacing) => println!("spacing {}",spacing),
+                None => println!("spacing none"),
+            }
+            for (i, cluster) in clustering.clusters(k).iter().enumerate() {
+                let cluster_str : Vec<String> = cluster.iter().map(|v| v.to_string()).collect();
+                println!("cluster {}: {}",i + 1,cluster_str.join(" "));
+            }
+        }
+    }
+    Ok(tree)
+}
 
-        // split the line into the vertex and the list of adjacent vertexes/weight pairs
-        let re_vertex = Regex::new(r"\s*(?P<src>\d+)\s+(?P<dest>\d+)\s+(?P<weight>-*\d+).*$").unwrap();
-        // adjacent vertexes are in the format vertex,weight   - and regex below allows for
-        // whitespace
-        let caps = re_vertex.captures(&line_data).unwrap();
-        let src_vertex = caps["src"].parse::<usize>().unwrap(); 
-        let dest_vertex = caps["dest"].parse::<usize>().unwrap(); 
-        let weight = caps["weight"].parse::<i32>().unwrap(); 
-        g.add_edge(src_vertex,dest_vertex,weight);
-        g.add_edge(dest_vertex,src_vertex,weight);
-  //      println!("Added Edge #{}: from {} - {} wgt: {} --  ",_count,src_vertex,dest_vertex,weight);
+// the compact graph only supports the algorithms that are generic over GraphView
+fn run_csr<W: Weight>(cmd_line: &CommandArgs, options: &LoadOptions) -> Result<()> {
+    let g = load_csr_graph::<W>(&cmd_line.filename,options)?;
+    info!("Loaded {} vertexes and {} edges using {} bytes",g.vertex_count(),g.edge_count(),g.memory_size());
+
+    match cmd_line.mode {
+        Mode::Mst => {
+            run_mst(cmd_line,&g)?;
+        },
+        Mode::Dijkstra => {
+            if g.has_negative_edges() {
+                error!("{} has negative edge weights, use --mode bellman-ford instead",cmd_line.filename);
+                process::exit(1);
+            }
+            let mut d = Dijkstra::new();
+            match cmd_line.target_vertex {
+                Some(target) => print_path(d.shortest_path(&g,cmd_line.start_vertex,target)?,cmd_line.start_vertex,target),
+                None => print_paths(&d.shortest_paths(&g,cmd_line.start_vertex)?,None),
+            }
+        },
+        Mode::AllPairs => print_min_shortest_path(&all_pairs::all_pairs_shortest_paths(&g)?),
+        _ => {
+            error!("--csr only supports the mst, dijkstra and all-pairs modes");
+            process::exit(1);
+        },
     }
+    Ok(())
+}
 
-    for v in g.vertex_map.keys() {
-        p.unprocessed_vertex.insert(g.vertex_map[v].vertex_id,100000000);
+fn run<W: Weight>(cmd_line: &CommandArgs) -> Result<()> {
+
+    info!("Processing {} starting from Vertex {}",cmd_line.filename,cmd_line.start_vertex);
+    let mut options = LoadOptions::new(cmd_line.directed,cmd_line.strict);
+    options.format = cmd_line.format;
+    options.csv = cmd_line.csv.clone();
+    options.fast = cmd_line.fast.clone();
+    options.parallel_edges = cmd_line.parallel_edges;
+    options.self_loops = cmd_line.self_loops;
+    if cmd_line.csr {
+        return run_csr::<W>(cmd_line,&options);
     }
+    let g = load_graph::<W>(&cmd_line.filename,&options)?;
+    info!("Loaded {} vertexes and {} edges",g.vertex_map.len(),g.edge_count());
 
 //    println!("At Start");
 
 //   g.print_vertexes();
-    p.min_span_tree(&mut g,cmd_line.start_vertex);
+    let mut mst = None;
+    match cmd_line.mode {
+        Mode::Mst => {
+            // only the best tree is highlighted in an export
+            mst = run_mst(cmd_line,&g)?;
+        },
+        Mode::Dijkstra => {
+            if g.has_negative_edges() {
+                error!("{} has negative edge weights, use --mode bellman-ford instead",cmd_line.filename);
+                process::exit(1);
+            }
+            let mut d = Dijkstra::new();
+            match cmd_line.target_vertex {
+                // stops searching once the target is reached
+                Some(target) => print_path(d.shortest_path(&g,cmd_line.start_vertex,target)?,cmd_line.start_vertex,target),
+                None => print_paths(&d.shortest_paths(&g,cmd_line.start_vertex)?,None),
+            }
+        },
+        Mode::BellmanFord => {
+            let paths = BellmanFord::new().shortest_paths(&g,cmd_line.start_vertex)?;
+            if let Some(target) = cmd_line.target_vertex {
+                if !g.vertex_map.contains_key(&target) {
+                    return Err(Error::UnknownVertex(target));
+                }
+            }
+            print_paths(&paths,cmd_line.target_vertex);
+        },
+        Mode::AllPairs => print_min_shortest_path(&all_pairs::all_pairs_shortest_paths(&g)?),
+    }
 
-    let mut sum = 0;
-    for v in p.processed_vertex {
-        sum += v.1;
- //       println!("v {:?} sub-total {}",v,sum);
+    if let Some((export_file,export_format)) = &cmd_line.export {
+        info!("Exporting to {} as {:?}",export_file,export_format);
+        export_to_file(export_file,&g,mst.as_ref(),*export_format)?;
     }
-    println!("{}",sum);
+    Ok(())
 
 }
 
+fn main() {
+
+    // show warnings (e.g. header mismatches) unless RUST_LOG says otherwise
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
 
-/*
- * the rest of this file sets up unit tests
- * to run these, the command will be:
- * cargo test --package rust-template -- --nocapture
- * Note: 'rust-template' comes from Cargo.toml's 'name' key
- */
-
-// use the attribute below for unit tests
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::graph::Edge;
-
-	fn setup_basic1() -> Graph {
-		let mut g = Graph::new();
-		assert_eq!(g.add_edge(1,2,1),Some(1));
-        assert_eq!(g.add_edge(1,3,1),Some(2));
-        assert_eq!(g.add_edge(2,3,1),Some(1));
-        assert_eq!(g.add_edge(2,4,1),Some(2));
-        assert_eq!(g.add_edge(3,4,1),Some(1));
-        assert_eq!(g.get_outgoing(1),&[Edge::new(2,1),Edge::new(3,1)]);
-		assert_eq!(g.get_outgoing(2),&[Edge::new(3,1),Edge::new(4,1)]);
-		assert_eq!(g.get_outgoing(3),&[Edge::new(4,1)]);
-		assert_eq!(g.get_outgoing(4),&[]);
-		g
-	} 
-
-    #[test]
-    fn basic() {
-		let mut g = Graph::new();
-		assert_eq!(g.create_vertex(&1),Some(1));
-		assert_eq!(g.create_vertex(&2),Some(2));
-		assert_eq!(g.add_edge(1,2,1),Some(1));
-		assert_eq!(g.get_vertexes(),vec!(1,2));
-		assert_eq!(g.create_vertex(&3),Some(3));
-		assert_eq!(g.add_edge(1,3,1),Some(2));
-		assert_eq!(g.add_edge(2,3,1),Some(1));
-		assert_eq!(g.get_vertexes(),vec!(1,2,3));
-		assert_eq!(g.add_edge(1,4,1),Some(3));
-		assert_eq!(g.get_vertexes(),vec!(1,2,3,4));
-		println!("{:?}",g);
-
-    }
-
-	#[test]
-	fn test_add() {
-		let mut g = Graph::new();
-		assert_eq!(g.add_edge(1,2,1),Some(1));
-		assert_eq!(g.get_outgoing(1),&[Edge::new(2,1)]);
-		assert_eq!(g.get_incoming(2),&[Edge::new(1,1)]);
-		assert_eq!(g.add_edge(1,3,1),Some(2));
-		assert_eq!(g.get_outgoing(1),&[Edge::new(2,1),Edge::new(3,1)]);
-		assert_eq!(g.get_incoming(2),&[Edge::new(1,1)]);
-	}
-
-	#[test]
-	fn test_add_del() {
-		let mut g = setup_basic1();
-		assert_eq!(g.get_outgoing(1),&[Edge::new(2,1),Edge::new(3,1)]);
-		assert_eq!(g.add_edge(1,2,1),Some(3));
-		assert_eq!(g.get_outgoing(1),&[Edge::new(2,1),Edge::new(3,1)]);
-		assert_eq!(g.get_outgoing(2),&[Edge::new(3,1),Edge::new(4,1)]);
-		assert_eq!(g.get_outgoing(3),&[Edge::new(4,1)]);
-		assert_eq!(g.delete_edge(1,2,1),Ok(()));
-		assert_eq!(g.get_outgoing(1),&[Edge::new(2,1),Edge::new(3,1)]);
-		assert_eq!(g.delete_edge(1,2,1),Ok(()));
-		assert_eq!(g.get_outgoing(1),&[Edge::new(3,1)]);
-		
-	}
-
-
- }
+    let cmd_line = CommandArgs::new();
+
+//    println!("Hello, {:?}!",cmd_line);
+
+    let result = match cmd_line.weight_type {
+        WeightType::I32 => run::<i32>(&cmd_line),
+        WeightType::I64 => run::<i64>(&cmd_line),
+        WeightType::U64 => run::<u64>(&cmd_line),
+        WeightType::F64 => run::<f64>(&cmd_line),
+    };
+    if let Err(e) = result {
+        eprintln!("Error: {}",e);
+        process::exit(1);
+    }
+
+}
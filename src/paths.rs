@@ -0,0 +1,51 @@
+use std::collections::BTreeMap;
+use crate::weight::Weight;
+
+
+// result of a single source shortest path search
+#[derive(Debug,Clone,Default)]
+pub struct ShortestPaths<W = i32> {
+    pub source: usize,
+    // distance from the source to every reachable vertex
+    pub distance: BTreeMap::<usize,W>,
+    // previous vertex on the shortest path from the source (source has none)
+    pub predecessor: BTreeMap::<usize,usize>,
+}
+
+impl<W: Weight> ShortestPaths<W> {
+
+    pub fn new(source: usize) -> Self {
+        ShortestPaths {
+            source,
+            distance: BTreeMap::<usize,W>::new(),
+            predecessor: BTreeMap::<usize,usize>::new(),
+        }
+    }
+
+    pub fn is_reachable(&self, vertex: usize) -> bool {
+        self.distance.contains_key(&vertex)
+    }
+
+    // walk the predecessor links back from vertex to the source
+    pub fn path_to(&self, vertex: usize) -> Option<Vec<usize>> {
+        if !self.is_reachable(vertex) {
+            return None;
+        }
+        let mut path = vec!(vertex);
+        let mut cur = vertex;
+        while let Some(prev) = self.predecessor.get(&cur) {
+            path.push(*prev);
+            cur = *prev;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+}
+
+
+// a cycle whose total weight is negative, listed with the first vertex repeated at the end
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct NegativeCycle {
+    pub vertices: Vec<usize>,
+}
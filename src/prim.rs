@@ -1,52 +1,98 @@
+use std::cmp::Ordering;
 use std::collections::{HashMap};
 extern crate minheap;
 use minheap::MinHeap;
-use crate::graph::Graph;
-use log::{info,error};
+use crate::graph::GraphView;
+use crate::weight::Weight;
+use crate::spanning_tree::{SpanningTree,TreeKind};
+use crate::error::{Error,Result};
+use log::info;
 
 
 
-pub struct Prim {
-    pub unprocessed_vertex : MinHeap::<i32>,
-    pub processed_vertex : HashMap::<usize,i32>,
+// the heap pops the smallest score, so scores are ordered by TreeKind::compare_edges
+// on the edge's (weight, low vertex, high vertex)
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct Score<W> {
+    pub weight: W,
+    pub edge: (usize,usize),
+    pub kind: TreeKind,
+}
+
+impl<W: Weight> PartialOrd for Score<W> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.kind.compare_edges(&(self.weight,self.edge.0,self.edge.1),&(other.weight,other.edge.0,other.edge.1)))
+    }
+}
+
+
+pub struct Prim<W = i32> {
+    pub unprocessed_vertex : MinHeap::<Score<W>>,
+    pub processed_vertex : HashMap::<usize,W>,
+    // vertex that currently offers the best edge to each unprocessed vertex
+    pub parent : HashMap::<usize,usize>,
+    pub kind : TreeKind,
 }
             
 
-impl Prim {
+impl<W: Weight> Default for Prim<W> {
+    fn default() -> Self {
+        Prim::new()
+    }
+}
+
+impl<W: Weight> Prim<W> {
 
     pub fn new() -> Self {
 
         Prim  {
-            unprocessed_vertex : MinHeap::<i32>::new(),
-            processed_vertex : HashMap::<usize,i32>::new(),
+            unprocessed_vertex : MinHeap::<Score<W>>::new(),
+            processed_vertex : HashMap::<usize,W>::new(),
+            parent : HashMap::<usize,usize>::new(),
+            kind : TreeKind::Minimum,
         }
     }
 
+    pub fn maximum() -> Self {
+        Prim { kind : TreeKind::Maximum, ..Prim::new() }
+    }
+
 
     // update scoring for Prim MST  
     
-    pub fn update_scoring(&mut self, graph: &mut Graph, id: usize) {
-        // get a vector of outgoing edges... (comprised of vertex and weight
-        let adj_vertexes = graph.get_outgoing(id);
-        
-        // update each of this nodes adjancent vertexes, 
+    pub fn update_scoring<G: GraphView<Weight=W>>(&mut self, graph: &G, id: usize) -> Result<()> {
+        // update each of this nodes adjancent vertexes (comprised of vertex and weight),
         // setting their score to their weight
-        for edge in adj_vertexes {
-            if let Some(cur_score) = self.unprocessed_vertex.peek_id_data(edge.vertex) {
-            //    println!("Edge to vertex {} has weight {}",edge.vertex,cur_score);
-                if edge.weight < cur_score {
-                    let vertex_index= self.unprocessed_vertex.get_id_index(edge.vertex).unwrap().clone() ;
-                    self.unprocessed_vertex.update(vertex_index,edge.weight);
+        for (vertex, weight) in graph.neighbors(id)? {
+            let score = Score { weight, edge: (id.min(vertex),id.max(vertex)), kind: self.kind };
+            if let Some(cur_score) = self.unprocessed_vertex.peek_id_data(vertex) {
+            //    println!("Edge to vertex {} has weight {}",vertex,cur_score);
+                if score < cur_score {
+                    let vertex_index= *self.unprocessed_vertex.get_id_index(vertex).unwrap() ;
+                    self.unprocessed_vertex.update(vertex_index,score);
+                    self.parent.insert(vertex,id);
                 }
             }
+            else if !self.processed_vertex.contains_key(&vertex) {
+                // first time this vertex has been reached, so the edge weight is its score
+                self.unprocessed_vertex.insert(vertex,score);
+                self.parent.insert(vertex,id);
+            }
             else {
              //   println!("skipping... already processed");
             }
             //println!("Done with {:?}",edge)
 
         }
+        Ok(())
 
+    }
 
+    // forget the previous run so the same instance can build another tree
+    fn reset(&mut self) {
+        self.unprocessed_vertex = MinHeap::<Score<W>>::new();
+        self.processed_vertex.clear();
+        self.parent.clear();
     }
 
     pub fn display(&self) {
@@ -70,38 +116,58 @@ impl Prim {
 
     }
 
-    pub fn min_span_tree(&mut self, graph: &mut Graph, starting_vertex: usize) {
+    // grow a tree from root until no more vertexes can be reached, adding
+    // each vertex's attaching edge to the current component of the tree
+    fn span_component<G: GraphView<Weight=W>>(&mut self, graph: &G, root: usize, tree: &mut SpanningTree<W>) -> Result<()> {
+
+        // setup the initial distance for the root vertex to 0 (to itself)
+        self.processed_vertex.insert(root,W::ZERO);
+        tree.start_component(root);
+
+        // update the scoring in the unprocessed heap so the next vertex is that the top
+        self.update_scoring(graph,root)?;
+
+        // pull out each vertex from the heap, add it to processed list, and
+        // update the weights based on the adjacent vertexes, and then select the 
+        // closeset one, repeating until the heap is empty (all reachable vertexes have been processed)
+        while let Some((next_vertex,Score { weight: next_vertex_score, .. })) = self.unprocessed_vertex.get_min_entry() {
+      //      println!("Processing vertex {} score: {}",next_vertex,next_vertex_score);
+            self.processed_vertex.insert(next_vertex,next_vertex_score);
+            // vertexes only enter the heap through an edge, so every one has a parent
+            let parent = self.parent[&next_vertex];
+            tree.add_edge(parent,next_vertex,next_vertex_score);
+            self.update_scoring(graph,next_vertex)?;
+//            self.display();
+        }
+        Ok(())
+    }
+
+    // builds a minimum (or maximum, depending on kind) spanning forest, starting with the component containing
+    // starting_vertex and then restarting from the lowest unprocessed vertex
+    // for each remaining component
+    pub fn spanning_tree<G: GraphView<Weight=W>>(&mut self, graph: &G, starting_vertex: usize) -> Result<SpanningTree<W>> {
+        let mut tree = SpanningTree::new();
         info!("Starting Min Span Tree path with {}",starting_vertex);
-        //println!("Unprocessed: {:?}",self.unprocessed_vertex);
 
-        if let Some(starting_index) = self.unprocessed_vertex.get_id_index(starting_vertex) {
+        if graph.is_directed() {
+            return Err(Error::DirectedGraph);
+        }
 
-            let index = starting_index.clone();
-            self.unprocessed_vertex.delete(index);
-         //   println!("Unprocessed After Delete: {:?}",self.unprocessed_vertex);
-            
-            // setup the initial distance for the starting vertex to 0 (to itself)
-            self.processed_vertex.insert(starting_vertex,0);
+        if !graph.contains_vertex(starting_vertex) {
+            return Err(Error::InvalidStartVertex(starting_vertex));
+        }
 
-            // update the scoring in the unprocessed heap so the next vertex is that the top
-            self.update_scoring(graph,starting_vertex);
+        self.reset();
+        self.span_component(graph,starting_vertex,&mut tree)?;
 
-            
-        //    println!("Afer initial vertex selection");
-        //   self.display();
-            // pull out each vertex from the heap, add it to processed list, and
-            // update the weights based on the adjacent vertexes, and then select the 
-            // closeset one, repeating until the heap is empty (all vertexes have been processed)
-            while let Some((next_vertex,next_vertex_score)) = self.unprocessed_vertex.get_min_entry() {
-          //      println!("Processing vertex {} score: {}",next_vertex,next_vertex_score);
-                self.processed_vertex.insert(next_vertex,next_vertex_score);
-                self.update_scoring(graph,next_vertex);
-//                self.display();
+        for v in graph.vertices() {
+            if !self.processed_vertex.contains_key(&v) {
+                info!("Vertex {} is unreachable, starting new component",v);
+                self.span_component(graph,v,&mut tree)?;
             }
-         }       
-        else {
-            error!("Starting vertex {} is not in the graph",starting_vertex);
         }
+        info!("Spanning forest has {} component(s)",tree.component_count());
+        Ok(tree)
 
     }
 
@@ -0,0 +1,490 @@
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::path::Path;
+use regex::Regex;
+use log::{info,warn};
+use crate::graph::Graph;
+use crate::error::{Error,Result};
+use crate::loader::LoadOptions;
+use crate::weight::Weight;
+use crate::edge_policy::{EdgeMerger,Merge};
+
+
+// parses one input format into a graph
+pub trait GraphReader<W: Weight = i32> {
+    // hands every vertex and edge read to sink, returning the (vertex count, edge count)
+    // header if the input has one
+    fn read_into(&self, reader: &mut dyn BufRead, options: &LoadOptions, sink: &mut dyn EdgeSink<W>) -> Result<Option<(usize,usize)>>;
+
+    fn read(&self, reader: &mut dyn BufRead, options: &LoadOptions) -> Result<Graph<W>> {
+        let mut builder = GraphBuilder::new(options);
+        let header = self.read_into(reader,options,&mut builder)?;
+        builder.finish(header)
+    }
+}
+
+
+// where a reader puts what it reads, so the same readers can fill a Graph (GraphBuilder)
+// or a CsrGraph (CsrBuilder)
+pub trait EdgeSink<W: Weight> {
+    fn add_vertex(&mut self, vertex: usize);
+    // edges are counted as read, before the load options merge or drop any of them
+    fn add_edge(&mut self, src: usize, dest: usize, weight: W) -> Result<()>;
+    // an edge the input implies without listing it, like the mirror of a symmetric
+    // matrix entry. The load options still apply but it isn't counted as read.
+    fn add_implied_edge(&mut self, src: usize, dest: usize, weight: W) -> Result<()>;
+}
+
+
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum InputFormat {
+    EdgeList,
+    Dimacs,
+    Csv,
+    MatrixMarket,
+    AdjacencyList,
+}
+
+impl InputFormat {
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "edges" | "edge-list" => Some(InputFormat::EdgeList),
+            "dimacs" | "gr" => Some(InputFormat::Dimacs),
+            "csv" | "tsv" => Some(InputFormat::Csv),
+            "mtx" | "matrix-market" => Some(InputFormat::MatrixMarket),
+            "adj" | "adjacency" => Some(InputFormat::AdjacencyList),
+            _ => None,
+        }
+    }
+
+    // guess the format from the file extension, anything unknown is an edge list
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("gr") => InputFormat::Dimacs,
+            Some("csv") | Some("tsv") => InputFormat::Csv,
+            Some("mtx") => InputFormat::MatrixMarket,
+            Some("adj") => InputFormat::AdjacencyList,
+            _ => InputFormat::EdgeList,
+        }
+    }
+
+    pub fn reader<W: Weight>(&self, options: &LoadOptions) -> Box<dyn GraphReader<W>> {
+        match self {
+            InputFormat::EdgeList => Box::new(EdgeListReader),
+            InputFormat::Dimacs => Box::new(DimacsReader),
+            InputFormat::Csv => Box::new(options.csv.clone()),
+            InputFormat::MatrixMarket => Box::new(MatrixMarketReader),
+            InputFormat::AdjacencyList => Box::new(AdjacencyListReader),
+        }
+    }
+
+}
+
+
+// collects vertexes and edges for every reader so that directed/undirected handling
+// and the header checks work the same way whatever the input format
+pub struct GraphBuilder<'a, W = i32> {
+    pub graph: Graph<W>,
+    options: &'a LoadOptions,
+    merger: EdgeMerger<W>,
+    edge_count: usize,
+}
+
+impl<'a, W: Weight> GraphBuilder<'a, W> {
+
+    pub fn new(options: &'a LoadOptions) -> Self {
+        let graph = if options.directed { Graph::new() } else { Graph::new_undirected() };
+        let merger = EdgeMerger::new(options.parallel_edges,options.self_loops,options.directed);
+        GraphBuilder { graph, options, merger, edge_count: 0 }
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+
+    // header is the (vertex count, edge count) declared by the input, if it has one
+    pub fn finish(self, header: Option<(usize,usize)>) -> Result<Graph<W>> {
+        info!("Loaded {} edges",self.edge_count);
+        self.merger.log_counts();
+        check_header(self.options,header,self.graph.vertex_map.len(),self.edge_count)?;
+        Ok(self.graph)
+    }
+
+}
+
+impl<W: Weight> EdgeSink<W> for GraphBuilder<'_, W> {
+
+    fn add_vertex(&mut self, vertex: usize) {
+        self.graph.create_vertex(&vertex);
+    }
+
+    fn add_edge(&mut self, src: usize, dest: usize, weight: W) -> Result<()> {
+        self.edge_count += 1;
+        self.add_implied_edge(src,dest,weight)
+    }
+
+    fn add_implied_edge(&mut self, src: usize, dest: usize, weight: W) -> Result<()> {
+        match self.merger.add(src,dest,weight,0)? {
+            Merge::Add => { self.graph.add_edge(src,dest,weight); },
+            Merge::Replace { old, new, .. } => {
+                self.graph.delete_edge(src,dest,old)?;
+                self.graph.add_edge(src,dest,new);
+            },
+            Merge::Skip => (),
+        }
+        Ok(())
+    }
+
+}
+
+
+// compare the counts declared by a header against what was actually read
+pub fn check_header(options: &LoadOptions, header: Option<(usize,usize)>, vertexes: usize, edges: usize) -> Result<()> {
+    let Some((expected_vertexes, expected_edges)) = header else { return Ok(()) };
+    for (what, expected, found) in [("vertexes",expected_vertexes,vertexes),("edges",expected_edges,edges)] {
+        if expected != found {
+            if options.strict {
+                return Err(Error::HeaderMismatch { what: what.to_string(), expected, found });
+            }
+            warn!("Header declares {} {} but {} were read",expected,what,found);
+        }
+    }
+    Ok(())
+}
+
+
+// splits a line into fields along with the column (starting at 1) each one starts at
+pub(crate) fn fields(line: &str, delimiter: Option<char>) -> Vec<(usize,&str)> {
+    match delimiter {
+        None => {
+            let mut fields = Vec::<(usize,&str)>::new();
+            let mut start = None;
+            for (i, c) in line.char_indices() {
+                match (c.is_whitespace(), start) {
+                    (false, None) => start = Some(i),
+                    (true, Some(s)) => { fields.push((s + 1,&line[s..i])); start = None; },
+                    _ => (),
+                }
+            }
+            if let Some(s) = start {
+                fields.push((s + 1,&line[s..]));
+            }
+            fields
+        },
+        Some(delimiter) => {
+            let mut column = 1;
+            line.split(delimiter).map(|f| {
+                let field = (column + f.len() - f.trim_start().len(), f.trim());
+                column += f.len() + delimiter.len_utf8();
+                field
+            }).collect()
+        },
+    }
+}
+
+// column (starting at 1) of the first non-whitespace character in the line
+fn first_column(line: &str) -> usize {
+    line.len() - line.trim_start().len() + 1
+}
+
+pub(crate) fn parse_field<T: std::str::FromStr>(field: Option<&(usize,&str)>, line_num: usize, line_data: &str, name: &str) -> Result<T> {
+    parse_field_with(field,line_num,line_data,name,|value| value.parse::<T>().ok())
+}
+
+// weights go through Weight::parse so that float NaNs are rejected
+fn parse_weight<W: Weight>(field: Option<&(usize,&str)>, line_num: usize, line_data: &str, name: &str) -> Result<W> {
+    // name the type, so a decimal weight read as an integer explains itself
+    parse_field_with(field,line_num,line_data,&format!("{} {}",W::NAME,name),W::parse)
+}
+
+fn parse_field_with<T>(field: Option<&(usize,&str)>, line_num: usize, line_data: &str, name: &str, parse: impl Fn(&str) -> Option<T>) -> Result<T> {
+    match field {
+        Some((column, value)) => parse(value).ok_or_else(|| Error::Parse {
+            line: line_num,
+            column: *column,
+            message: format!("invalid {} \"{}\"",name,value),
+        }),
+        None => Err(Error::Parse {
+            line: line_num,
+            column: line_data.len() + 1,
+            message: format!("missing {}",name),
+        }),
+    }
+}
+
+pub(crate) fn read_lines(reader: &mut dyn BufRead) -> impl Iterator<Item=Result<(usize,String)>> + '_ {
+    reader.lines().enumerate().map(|(i, line)| {
+        line.map(|l| (i + 1,l)).map_err(|e| Error::Io { path: format!("line {}",i + 1), source: e })
+    })
+}
+
+
+// "src dest weight" lines following a "vertex_count edge_count" header, a line with
+// just a vertex declares a vertex with no edges
+pub struct EdgeListReader;
+
+impl EdgeListReader {
+
+    // the header is "vertex_count edge_count"
+    pub fn parse_header(line_data: &str) -> Result<(usize,usize)> {
+        let fields : Vec<&str> = line_data.split_whitespace().collect();
+        if let [vertexes, edges] = fields[..] {
+            if let (Ok(v), Ok(e)) = (vertexes.parse::<usize>(), edges.parse::<usize>()) {
+                return Ok((v,e));
+            }
+        }
+        Err(Error::Parse {
+            line: 1,
+            column: first_column(line_data),
+            message: format!("expected header \"vertex_count edge_count\" but found \"{}\"",line_data.trim()),
+        })
+    }
+
+}
+
+impl<W: Weight> GraphReader<W> for EdgeListReader {
+
+    fn read_into(&self, reader: &mut dyn BufRead, _options: &LoadOptions, sink: &mut dyn EdgeSink<W>) -> Result<Option<(usize,usize)>> {
+        let mut header = None;
+
+        // split the line into the source, destination and weight - the regex allows for
+        // surrounding whitespace and anything trailing the weight
+        let re_vertex = Regex::new(r"^\s*(?P<src>\S+)\s+(?P<dest>\S+)\s+(?P<weight>\S+).*$").unwrap();
+
+        for line in read_lines(reader) {
+            let (line_num, line_data) = line?;
+            if line_num == 1 {
+                header = Some(EdgeListReader::parse_header(&line_data)?);
+                continue;
+            }
+            if line_data.trim().is_empty() {
+                continue;
+            }
+
+            // an isolated vertex
+            if let Ok(vertex) = line_data.trim().parse::<usize>() {
+                sink.add_vertex(vertex);
+                continue;
+            }
+
+            let caps = re_vertex.captures(&line_data).ok_or_else(|| Error::Parse {
+                line: line_num,
+                column: first_column(&line_data),
+                message: format!("expected \"src dest weight\" but found \"{}\"",line_data.trim()),
+            })?;
+
+            // report the column of whichever field failed to parse
+            let field = |name: &str| {
+                let m = caps.name(name).unwrap();
+                (m.start() + 1, m.as_str())
+            };
+
+            let src_vertex = parse_field::<usize>(Some(&field("src")),line_num,&line_data,"source vertex")?;
+            let dest_vertex = parse_field::<usize>(Some(&field("dest")),line_num,&line_data,"destination vertex")?;
+            let weight = parse_weight::<W>(Some(&field("weight")),line_num,&line_data,"weight")?;
+
+            sink.add_edge(src_vertex,dest_vertex,weight)?;
+        }
+        Ok(header)
+    }
+
+}
+
+
+// DIMACS shortest path format: "c" comment lines, a "p sp vertexes arcs" problem
+// line and "a src dest weight" arc lines
+pub struct DimacsReader;
+
+impl<W: Weight> GraphReader<W> for DimacsReader {
+
+    fn read_into(&self, reader: &mut dyn BufRead, _options: &LoadOptions, sink: &mut dyn EdgeSink<W>) -> Result<Option<(usize,usize)>> {
+        let mut header = None;
+
+        for line in read_lines(reader) {
+            let (line_num, line_data) = line?;
+            let fields = fields(&line_data,None);
+            match fields.first().map(|f| f.1) {
+                None | Some("c") => (),
+                Some("p") => {
+                    let vertexes = parse_field::<usize>(fields.get(2),line_num,&line_data,"vertex count")?;
+                    let arcs = parse_field::<usize>(fields.get(3),line_num,&line_data,"arc count")?;
+                    // vertexes are numbered 1..n, any without arcs are isolated
+                    for v in 1..=vertexes {
+                        sink.add_vertex(v);
+                    }
+                    header = Some((vertexes,arcs));
+                },
+                Some("a") => {
+                    let src = parse_field::<usize>(fields.get(1),line_num,&line_data,"source vertex")?;
+                    let dest = parse_field::<usize>(fields.get(2),line_num,&line_data,"destination vertex")?;
+                    let weight = parse_weight::<W>(fields.get(3),line_num,&line_data,"weight")?;
+                    sink.add_edge(src,dest,weight)?;
+                },
+                Some(other) => return Err(Error::Parse {
+                    line: line_num,
+                    column: fields[0].0,
+                    message: format!("unknown DIMACS line type \"{}\"",other),
+                }),
+            }
+        }
+        Ok(header)
+    }
+
+}
+
+
+// delimited edge list with configurable columns (starting at 0), edges get a weight
+// of 1 when there is no weight column
+#[derive(Debug,Clone)]
+pub struct CsvReader {
+    pub delimiter: char,
+    pub src_column: usize,
+    pub dest_column: usize,
+    pub weight_column: Option<usize>,
+    // skip the first row as column names
+    pub has_header: bool,
+}
+
+impl CsvReader {
+    pub fn new(delimiter: char) -> Self {
+        CsvReader { delimiter, src_column: 0, dest_column: 1, weight_column: Some(2), has_header: false }
+    }
+}
+
+impl Default for CsvReader {
+    fn default() -> Self {
+        CsvReader::new(',')
+    }
+}
+
+impl<W: Weight> GraphReader<W> for CsvReader {
+
+    fn read_into(&self, reader: &mut dyn BufRead, _options: &LoadOptions, sink: &mut dyn EdgeSink<W>) -> Result<Option<(usize,usize)>> {
+
+        for line in read_lines(reader) {
+            let (line_num, line_data) = line?;
+            if (line_num == 1 && self.has_header) || line_data.trim().is_empty() {
+                continue;
+            }
+            let fields = fields(&line_data,Some(self.delimiter));
+            let src = parse_field::<usize>(fields.get(self.src_column),line_num,&line_data,"source vertex")?;
+            let dest = parse_field::<usize>(fields.get(self.dest_column),line_num,&line_data,"destination vertex")?;
+            let weight = match self.weight_column {
+                Some(column) => parse_weight::<W>(fields.get(column),line_num,&line_data,"weight")?,
+                None => W::ONE,
+            };
+            sink.add_edge(src,dest,weight)?;
+        }
+        Ok(None)
+    }
+
+}
+
+
+// Matrix Market coordinate format: a "%%MatrixMarket matrix coordinate <field> <symmetry>"
+// banner, "%" comments, a "rows cols entries" size line and then "row col [value]" entries
+pub struct MatrixMarketReader;
+
+impl<W: Weight> GraphReader<W> for MatrixMarketReader {
+
+    fn read_into(&self, reader: &mut dyn BufRead, options: &LoadOptions, sink: &mut dyn EdgeSink<W>) -> Result<Option<(usize,usize)>> {
+        let mut header = None;
+        let mut pattern = false;
+        let mut symmetric = false;
+
+        for line in read_lines(reader) {
+            let (line_num, line_data) = line?;
+            if line_num == 1 {
+                let banner : Vec<String> = line_data.split_whitespace().map(|f| f.to_lowercase()).collect();
+                if banner.len() < 5 || banner[0] != "%%matrixmarket" || banner[1] != "matrix" || banner[2] != "coordinate" {
+                    return Err(Error::Parse {
+                        line: 1,
+                        column: first_column(&line_data),
+                        message: "expected \"%%MatrixMarket matrix coordinate\" banner".to_string(),
+                    });
+                }
+                pattern = banner[3] == "pattern";
+                symmetric = banner[4] == "symmetric";
+                continue;
+            }
+            if line_data.starts_with('%') || line_data.trim().is_empty() {
+                continue;
+            }
+
+            let fields = fields(&line_data,None);
+            if header.is_none() {
+                let rows = parse_field::<usize>(fields.first(),line_num,&line_data,"row count")?;
+                let cols = parse_field::<usize>(fields.get(1),line_num,&line_data,"column count")?;
+                let entries = parse_field::<usize>(fields.get(2),line_num,&line_data,"entry count")?;
+                for v in 1..=rows.max(cols) {
+                    sink.add_vertex(v);
+                }
+                header = Some((rows.max(cols),entries));
+                continue;
+            }
+
+            let row = parse_field::<usize>(fields.first(),line_num,&line_data,"row")?;
+            let col = parse_field::<usize>(fields.get(1),line_num,&line_data,"column")?;
+            let weight = if pattern { W::ONE } else { parse_weight::<W>(fields.get(2),line_num,&line_data,"value")? };
+            sink.add_edge(row,col,weight)?;
+            // symmetric matrices only store one triangle, so directed graphs need the mirror
+            // entry (undirected graphs already get both directions)
+            if symmetric && options.directed && row != col {
+                sink.add_implied_edge(col,row,weight)?;
+            }
+        }
+        Ok(header)
+    }
+
+}
+
+
+// one line per vertex: "vertex  n1,w1 n2,w2 ...". These files normally list each
+// undirected edge from both ends, so for undirected graphs an entry that matches one
+// already listed from the other end is taken as its mirror rather than a new edge
+pub struct AdjacencyListReader;
+
+impl<W: Weight> GraphReader<W> for AdjacencyListReader {
+
+    fn read_into(&self, reader: &mut dyn BufRead, options: &LoadOptions, sink: &mut dyn EdgeSink<W>) -> Result<Option<(usize,usize)>> {
+        // (src, dest) -> weights of edges added from dest's line whose mirror src's line
+        // hasn't listed yet
+        let mut unmirrored = HashMap::<(usize,usize),Vec<W>>::new();
+
+        for line in read_lines(reader) {
+            let (line_num, line_data) = line?;
+            let fields = fields(&line_data,None);
+            if fields.is_empty() {
+                continue;
+            }
+            let src = parse_field::<usize>(fields.first(),line_num,&line_data,"vertex")?;
+            sink.add_vertex(src);
+
+            for (column, pair) in &fields[1..] {
+                let (dest, weight) = pair.split_once(',').ok_or_else(|| Error::Parse {
+                    line: line_num,
+                    column: *column,
+                    message: format!("expected \"vertex,weight\" but found \"{}\"",pair),
+                })?;
+                let dest_column = *column;
+                let weight_column = column + dest.len() + 1;
+                let dest = parse_field::<usize>(Some(&(dest_column,dest)),line_num,&line_data,"adjacent vertex")?;
+                let weight = parse_weight::<W>(Some(&(weight_column,weight)),line_num,&line_data,"weight")?;
+
+                if !options.directed && src != dest {
+                    let pending = unmirrored.entry((src,dest)).or_default();
+                    if let Some(i) = pending.iter().position(|w| w.total_cmp(&weight).is_eq()) {
+                        pending.swap_remove(i);
+                        continue;
+                    }
+                    unmirrored.entry((dest,src)).or_default().push(weight);
+                }
+                // genuine parallel edges are left to the parallel edge policy
+                sink.add_edge(src,dest,weight)?;
+            }
+        }
+        Ok(None)
+    }
+
+}
@@ -0,0 +1,169 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap,HashMap,HashSet,VecDeque};
+use crate::graph::Graph;
+use crate::weight::Weight;
+
+
+// whether the algorithms look for the lightest or the heaviest spanning tree
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default)]
+pub enum TreeKind {
+    #[default]
+    Minimum,
+    Maximum,
+}
+
+impl TreeKind {
+    // orders weights so the edge the tree prefers comes first
+    pub fn compare<W: Weight>(&self, a: &W, b: &W) -> Ordering {
+        match self {
+            TreeKind::Minimum => a.total_cmp(b),
+            TreeKind::Maximum => b.total_cmp(a),
+        }
+    }
+
+    // orders (weight, low vertex, high vertex) edges so the one the tree prefers comes
+    // first. Equal weights are ordered by (low, high), the tie break every spanning tree
+    // algorithm uses, so they all return the same tree and equal weight edges can never
+    // be picked in an order that closes a cycle.
+    pub fn compare_edges<W: Weight>(&self, a: &(W,usize,usize), b: &(W,usize,usize)) -> Ordering {
+        self.compare(&a.0,&b.0).then((a.1,a.2).cmp(&(b.1,b.2)))
+    }
+}
+
+
+// a single edge of a spanning tree, recorded as parent (src) -> child (dest)
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct TreeEdge<W = i32> {
+    pub src: usize,
+    pub dest: usize,
+    pub weight: W,
+}
+
+impl<W: Weight> TreeEdge<W> {
+    pub fn new(src: usize, dest: usize, weight: W) -> Self {
+        TreeEdge { src, dest, weight }
+    }
+}
+
+
+// one connected component of a spanning forest
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct TreeComponent<W = i32> {
+    pub root: usize,
+    pub vertices: Vec<usize>,
+    pub total_weight: W,
+}
+
+impl<W: Weight> TreeComponent<W> {
+    pub fn new(root: usize) -> Self {
+        TreeComponent { root, vertices: vec!(root), total_weight: W::ZERO }
+    }
+}
+
+
+// vertex -> (parent and the weight of the edge up to it, depth below the root), as
+// given by SpanningTree::ancestry
+pub type Ancestry<W> = HashMap<usize,(Option<(usize,W)>,usize)>;
+
+
+#[derive(Debug,Clone,Default)]
+pub struct SpanningTree<W = i32> {
+    // tree edges in the order they were added to the tree
+    pub edges: Vec<TreeEdge<W>>,
+    // a connected graph produces a single component, otherwise this is a forest
+    pub components: Vec<TreeComponent<W>>,
+}
+
+impl<W: Weight> SpanningTree<W> {
+
+    pub fn new() -> Self {
+        SpanningTree { 
+            edges: Vec::<TreeEdge<W>>::new(),
+            components: Vec::<TreeComponent<W>>::new(),
+        }
+    }
+
+    // builds a tree from an unordered set of tree edges, orienting each component
+    // as parent -> child starting from its first vertex in the order given
+    pub fn from_edges(vertices: &[usize], edges: &[TreeEdge<W>]) -> Self {
+        let mut adjacent = BTreeMap::<usize,Vec<(usize,W)>>::new();
+        for e in edges {
+            adjacent.entry(e.src).or_default().push((e.dest,e.weight));
+            adjacent.entry(e.dest).or_default().push((e.src,e.weight));
+        }
+
+        let mut tree = SpanningTree::new();
+        let mut visited = HashSet::<usize>::new();
+        for root in vertices {
+            if !visited.insert(*root) {
+                continue;
+            }
+            tree.start_component(*root);
+            let mut queue = VecDeque::<usize>::from(vec!(*root));
+            while let Some(v) = queue.pop_front() {
+                for (next,weight) in adjacent.get(&v).map(|a| a.as_slice()).unwrap_or(&[]) {
+                    if visited.insert(*next) {
+                        tree.add_edge(v,*next,*weight);
+                        queue.push_back(*next);
+                    }
+                }
+            }
+        }
+        tree
+    }
+
+    // subsequent edges are added to this new component
+    pub fn start_component(&mut self, root: usize) {
+        self.components.push(TreeComponent::new(root));
+    }
+
+    pub fn add_edge(&mut self, src: usize, dest: usize, weight: W) {
+        let component = self.components.last_mut().expect("start_component must be called before add_edge");
+        component.vertices.push(dest);
+        component.total_weight = component.total_weight + weight;
+        self.edges.push(TreeEdge::new(src,dest,weight));
+    }
+
+    pub fn total_weight(&self) -> W {
+        self.edges.iter().fold(W::ZERO,|total, e| total + e.weight)
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.components.len()
+    }
+
+    pub fn is_forest(&self) -> bool {
+        self.components.len() > 1
+    }
+
+    // each vertex's parent along with the weight of the edge up to it (None for the
+    // component roots), and its depth below its root
+    pub fn ancestry(&self) -> Ancestry<W> {
+        let mut ancestry = Ancestry::<W>::new();
+        for c in &self.components {
+            ancestry.insert(c.root,(None,0));
+        }
+        // edges point parent -> child and every parent is added before its children
+        for e in &self.edges {
+            let depth = ancestry[&e.src].1 + 1;
+            ancestry.insert(e.dest,(Some((e.src,e.weight)),depth));
+        }
+        ancestry
+    }
+
+    // build a new (undirected) graph containing only the tree edges
+    pub fn to_graph(&self) -> Graph<W> {
+        let mut g = Graph::new_undirected();
+        // isolated vertexes have no edges, so create every vertex up front
+        for c in &self.components {
+            for v in &c.vertices {
+                g.create_vertex(v);
+            }
+        }
+        for e in &self.edges {
+            g.add_edge(e.src,e.dest,e.weight);
+        }
+        g
+    }
+
+}
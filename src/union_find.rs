@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+
+
+// disjoint set forest using path compression and union by rank
+#[derive(Debug,Clone,Default)]
+pub struct UnionFind {
+    parent: HashMap::<usize,usize>,
+    rank: HashMap::<usize,u32>,
+    set_count: usize,
+}
+
+impl UnionFind {
+
+    pub fn new() -> Self {
+        UnionFind {
+            parent: HashMap::<usize,usize>::new(),
+            rank: HashMap::<usize,u32>::new(),
+            set_count: 0,
+        }
+    }
+
+    // add id as its own single element set (no-op if it already exists)
+    pub fn make_set(&mut self, id: usize) {
+        if let Entry::Vacant(entry) = self.parent.entry(id) {
+            entry.insert(id);
+            self.rank.insert(id,0);
+            self.set_count += 1;
+        }
+    }
+
+    pub fn contains(&self, id: usize) -> bool {
+        self.parent.contains_key(&id)
+    }
+
+    // number of disjoint sets currently tracked
+    pub fn set_count(&self) -> usize {
+        self.set_count
+    }
+
+    // returns the representative of the set containing id, compressing the
+    // path so every vertex visited points directly at the root
+    pub fn find(&mut self, id: usize) -> Option<usize> {
+        let mut root = *self.parent.get(&id)?;
+        while self.parent[&root] != root {
+            root = self.parent[&root];
+        }
+
+        let mut cur = id;
+        while cur != root {
+            let next = self.parent[&cur];
+            self.parent.insert(cur,root);
+            cur = next;
+        }
+        Some(root)
+    }
+
+    // merge the sets containing a and b, returns false if they were already
+    // in the same set (or either id is unknown)
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (root_a, root_b) = match (self.find(a), self.find(b)) {
+            (Some(ra), Some(rb)) => (ra, rb),
+            _ => return false,
+        };
+        if root_a == root_b {
+            return false;
+        }
+
+        let rank_a = self.rank[&root_a];
+        let rank_b = self.rank[&root_b];
+        if rank_a < rank_b {
+            self.parent.insert(root_a,root_b);
+        }
+        else if rank_a > rank_b {
+            self.parent.insert(root_b,root_a);
+        }
+        else {
+            self.parent.insert(root_b,root_a);
+            self.rank.insert(root_a,rank_a + 1);
+        }
+        self.set_count -= 1;
+        true
+    }
+
+}
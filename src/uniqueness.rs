@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+use crate::graph::GraphView;
+use crate::weight::Weight;
+use crate::kruskal::Kruskal;
+use crate::spanning_tree::{Ancestry,SpanningTree,TreeEdge,TreeKind};
+use crate::union_find::UnionFind;
+use crate::error::Result;
+use log::info;
+
+
+// a non tree edge that could take the place of any one of the tree edges in replaces,
+// giving a different tree with the same total weight
+#[derive(Debug,Clone,PartialEq)]
+pub struct Alternative<W = i32> {
+    pub edge: TreeEdge<W>,
+    pub replaces: Vec<TreeEdge<W>>,
+}
+
+
+#[derive(Debug,Clone,Default)]
+pub struct Uniqueness<W = i32> {
+    pub alternatives: Vec<Alternative<W>>,
+}
+
+impl<W: Weight> Uniqueness<W> {
+
+    pub fn is_unique(&self) -> bool {
+        self.alternatives.is_empty()
+    }
+
+    // checks whether tree (a minimum or maximum spanning forest of graph, as given by
+    // kind) is the only one. It isn't if a non tree edge weighs the same as the heaviest
+    // (lightest for maximum trees) edge on the tree path between its ends.
+    pub fn check<G: GraphView<Weight=W>>(graph: &G, tree: &SpanningTree<W>, kind: TreeKind) -> Result<Self> {
+        info!("Checking spanning tree uniqueness");
+
+        let ancestry = tree.ancestry();
+        let mut in_tree = HashMap::<(usize,usize),W>::new();
+        for e in &tree.edges {
+            in_tree.insert((e.src.min(e.dest),e.src.max(e.dest)),e.weight);
+        }
+        let is_tree_edge = |weight: &W, v1: usize, v2: usize| in_tree.get(&(v1,v2)).is_some_and(|w| w.total_cmp(weight).is_eq());
+
+        // take the edges a weight at a time as Kruskal would. A non tree edge that still
+        // joins two components once the lighter edges are in closes a cycle whose worst
+        // edge weighs the same as it does
+        let edges = Kruskal::ordered_edges(graph,kind)?;
+        let mut sets = UnionFind::new();
+        for v in graph.vertices() {
+            sets.make_set(v);
+        }
+        let mut alternatives = Vec::<Alternative<W>>::new();
+        let mut start = 0;
+        while start < edges.len() {
+            let weight = edges[start].0;
+            let end = start + edges[start..].iter().take_while(|e| e.0.total_cmp(&weight).is_eq()).count();
+            let group = &edges[start..end];
+
+            let mut candidates = Vec::<(usize,usize)>::new();
+            for (w, v1, v2) in group {
+                if !is_tree_edge(w,*v1,*v2) && sets.find(*v1) != sets.find(*v2) {
+                    candidates.push((*v1,*v2));
+                }
+            }
+            for (w, v1, v2) in group {
+                if is_tree_edge(w,*v1,*v2) {
+                    sets.union(*v1,*v2);
+                }
+            }
+            for (v1, v2) in candidates {
+                let replaces : Vec<TreeEdge<W>> = tree_path(&ancestry,v1,v2).into_iter()
+                    .filter(|e| e.weight.total_cmp(&weight).is_eq())
+                    .collect();
+                alternatives.push(Alternative { edge: TreeEdge::new(v1,v2,weight), replaces });
+            }
+            start = end;
+        }
+
+        info!("Found {} alternative edge(s)",alternatives.len());
+        Ok(Uniqueness { alternatives })
+    }
+
+}
+
+
+// the tree edges on the path between v1 and v2, found by climbing from the deeper end
+// until the two meet
+fn tree_path<W: Weight>(ancestry: &Ancestry<W>, v1: usize, v2: usize) -> Vec<TreeEdge<W>> {
+    let mut path = Vec::<TreeEdge<W>>::new();
+    let (mut a, mut b) = (v1,v2);
+    while a != b {
+        if ancestry[&a].1 < ancestry[&b].1 {
+            std::mem::swap(&mut a,&mut b);
+        }
+        // both are roots of different components, so there is no path
+        let (Some((p, weight)), _) = ancestry[&a] else { return Vec::<TreeEdge<W>>::new() };
+        path.push(TreeEdge::new(p,a,weight));
+        a = p;
+    }
+    path
+}
@@ -0,0 +1,71 @@
+use std::cmp::Ordering;
+use std::fmt::{Debug,Display};
+use std::ops::{Add,Sub};
+
+
+// an edge weight - anything that can be added, compared and parsed. Floats are allowed
+// because parse rejects NaN, so every weight that makes it into a graph has a total
+// order even though the type is only PartialOrd.
+pub trait Weight: Copy + Debug + Display + PartialOrd + Add<Output=Self> + Sub<Output=Self> + Send + Sync + 'static {
+    const ZERO: Self;
+    // the weight given to edges read from a file without weights
+    const ONE: Self;
+    // name used for the --weight-type option and in messages
+    const NAME: &'static str;
+    // written as integers by the exporters, otherwise as floating point
+    const INTEGRAL: bool;
+
+    fn parse(value: &str) -> Option<Self>;
+
+    // false for the infinities (and the NaN that adding opposite infinities gives),
+    // which formats like JSON can't write as numbers
+    fn is_finite(&self) -> bool {
+        true
+    }
+
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        self.partial_cmp(other).expect("weights can't be NaN")
+    }
+}
+
+macro_rules! integer_weight {
+    ($type:ty) => {
+        impl Weight for $type {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            const NAME: &'static str = stringify!($type);
+            const INTEGRAL: bool = true;
+
+            fn parse(value: &str) -> Option<Self> {
+                value.parse::<$type>().ok()
+            }
+
+            fn total_cmp(&self, other: &Self) -> Ordering {
+                self.cmp(other)
+            }
+        }
+    };
+}
+
+integer_weight!(i32);
+integer_weight!(i64);
+integer_weight!(u64);
+
+impl Weight for f64 {
+    const ZERO: Self = 0.0;
+    const ONE: Self = 1.0;
+    const NAME: &'static str = "f64";
+    const INTEGRAL: bool = false;
+
+    fn parse(value: &str) -> Option<Self> {
+        value.parse::<f64>().ok().filter(|w| !w.is_nan())
+    }
+
+    fn is_finite(&self) -> bool {
+        f64::is_finite(*self)
+    }
+
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        f64::total_cmp(self,other)
+    }
+}